@@ -1,13 +1,19 @@
+#![recursion_limit = "256"]
+
+#[macro_use]
 extern crate quote;
 extern crate proc_macro;
+extern crate proc_macro2;
 #[macro_use]
 extern crate syn;
 
 use proc_macro::TokenStream;
-use quote::Tokens;
+use proc_macro2::{Span, TokenStream as Tokens};
+use quote::ToTokens;
+use std::fmt::Display;
 
 use syn::DeriveInput;
-use syn::Meta::{List, NameValue};
+use syn::Meta::{List, NameValue, Word};
 use syn::NestedMeta::{Literal, Meta};
 use syn::Data::*;
 
@@ -17,351 +23,2751 @@ use syn::{Fields, Ident};
 pub fn postgres_mapper(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
-    impl_derive(&ast)
-        .parse()
-        .expect("Error parsing postgres mapper tokens")
+    match impl_derive(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(errors) => compile_errors(errors),
+    }
+}
+
+#[proc_macro_derive(PostgresEnum, attributes(pg_mapper))]
+pub fn postgres_enum(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    match impl_enum_derive(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(errors) => compile_errors(errors),
+    }
+}
+
+#[proc_macro_derive(PostgresComposite, attributes(pg_mapper))]
+pub fn postgres_composite(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    match impl_composite_derive(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(errors) => compile_errors(errors),
+    }
 }
 
-fn impl_derive(ast: &DeriveInput) -> Tokens {
+fn impl_derive(ast: &DeriveInput) -> Result<Tokens, Vec<syn::Error>> {
     #[allow(unused_mut)]
     let mut tokens = Tokens::new();
+    let mut errors = Errors::default();
+
+    let container = parse_container_attr(ast, &mut errors);
+
+    if let Some(ref type_name) = container.type_name {
+        errors.push(
+            type_name,
+            "pg_mapper container attribute `type_name` only applies to PostgresEnum and \
+             PostgresComposite",
+        );
+    }
 
+    // Types without a table only implement the table-independent traits.
+    #[allow(unused_variables)]
+    let table = container.table_name.as_ref().map(|table_name| Table {
+        schema: container.schema.as_ref().map(|schema| schema.value()),
+        name: table_name.value(),
+    });
 
+    if let (Some(schema), None) = (container.schema.as_ref(), container.table_name.as_ref()) {
+        errors.push(schema, "pg_mapper container attribute `schema` requires a `table`");
+    }
 
     #[allow(unused_variables)]
-    let fields: &Fields = match ast.data {
+    let mapped = match ast.data {
         Struct(ref s) => {
-            &s.fields
+            if let Some(ref tag) = container.tag {
+                errors.push(tag, "pg_mapper container attribute `tag` only applies to enums");
+            }
+
+            let fields = parse_fields(
+                &s.fields,
+                &ast.ident.to_string(),
+                container.rename_all,
+                &ast.generics,
+                &mut errors,
+            );
+
+            Mapped::Struct(fields)
+        },
+        Enum(ref e) => {
+            let column = match container.tag {
+                Some(ref tag) => tag.value(),
+                None => {
+                    errors.push(
+                        &ast.ident,
+                        "declare the tag column of the enum: #[pg_mapper(tag = \"foo\")], or \
+                         derive PostgresEnum for Postgres ENUM types",
+                    );
+
+                    String::new()
+                },
+            };
+
+            Mapped::Tagged {
+                column,
+                variants: parse_variants(
+                    &ast.ident,
+                    &e.variants,
+                    container.rename_all,
+                    &ast.generics,
+                    &mut errors,
+                ),
+            }
+        },
+        Union(_) => {
+            errors.push(&ast.ident, "Unions can not be mapped");
+
+            return errors.finish().map(|()| tokens);
         },
-        Enum(ref u) => {panic!("Enums can not be mapped")},
-        Union(ref u) => {panic!("Unions can not be mapped")},
     };
 
     #[allow(unused_variables)]
-    let table_name = parse_table_attr(&ast);
+    let quoting = if container.unquoted.is_some() { Quoting::Reserved } else { Quoting::All };
+
+    // Types borrowing from the row can only implement `FromPostgresRowBorrowed`,
+    // along with the SQL helpers of `PostgresFields` and `PostgresTable`.
+    #[allow(unused_variables)]
+    let borrows = mapped.fields().iter().any(|field| field.borrowed);
+
+    if !cfg!(all(feature = "postgres-support", feature = "postgres-mapper")) {
+        for field in mapped.fields().into_iter().filter(|field| field.borrowed) {
+            errors.push(
+                &field.ty,
+                "pg_mapper fields borrowing from the row require the `postgres-support` and \
+                 `postgres-mapper` features of postgres-mapper-derive",
+            );
+        }
+    }
+
+    if table.is_some() {
+        for field in unnamed_fields(&mapped) {
+            errors.push(
+                &field.ty,
+                format!(
+                    "pg_mapper field `{}` of a type with a table must declare its column name: \
+                     #[pg_mapper(column = \"...\")]",
+                    field.ident
+                ),
+            );
+        }
+    }
+
+    errors.finish()?;
+
+    #[cfg(feature = "postgres-mapper")]
+    {
+        if unnamed_fields(&mapped).is_empty() {
+            impl_postgres_fields(&mut tokens, ast, &mapped, quoting);
+
+            if let Some(table) = table.as_ref() {
+                impl_postgres_table(&mut tokens, ast, &mapped, table, quoting);
+            }
+        }
+    }
 
     #[cfg(feature = "postgres-support")]
     {
-        impl_from_row(&mut tokens, &ast.ident, &fields);
-        impl_from_borrowed_row(&mut tokens, &ast.ident, &fields);
+        if !borrows {
+            impl_from_row(&mut tokens, ast, &mapped);
+            impl_from_borrowed_row(&mut tokens, ast, &mapped);
+        }
 
         #[cfg(feature = "postgres-mapper")]
         {
-            impl_postgres_mapper(&mut tokens, &ast.ident, &fields, &table_name);
+            if !borrows {
+                impl_postgres_mapper(&mut tokens, ast, &mapped);
+            }
+
+            impl_postgres_borrowed_mapper(&mut tokens, ast, &mapped);
         }
     }
 
     #[cfg(feature = "tokio-postgres-support")]
     {
-        impl_tokio_from_row(&mut tokens, &ast.ident, &fields);
-        impl_tokio_from_borrowed_row(&mut tokens, &ast.ident, &fields);
+        if borrows {
+            return Ok(tokens);
+        }
+
+        impl_tokio_from_row(&mut tokens, ast, &mapped);
+        impl_tokio_from_borrowed_row(&mut tokens, ast, &mapped);
 
         #[cfg(feature = "postgres-mapper")]
         {
-            impl_tokio_postgres_mapper(&mut tokens, &ast.ident, &fields, &table_name);
+            impl_tokio_postgres_mapper(&mut tokens, ast, &mapped);
         }
     }
 
-    tokens
+    Ok(tokens)
 }
 
-#[cfg(feature = "postgres-support")]
-fn impl_from_row(t: &mut Tokens, struct_ident: &Ident, fields: &Fields) {
-    t.append(format!("
-impl<'a> From<::postgres::rows::Row<'a>> for {struct_name} {{
-    fn from(row: ::postgres::rows::Row<'a>) -> Self {{
-        Self {{", struct_name=struct_ident));
+fn impl_enum_derive(ast: &DeriveInput) -> Result<Tokens, Vec<syn::Error>> {
+    #[allow(unused_mut)]
+    let mut tokens = Tokens::new();
+    let mut errors = Errors::default();
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    let variants = match ast.data {
+        Enum(ref e) => &e.variants,
+        _ => {
+            errors.push(&ast.ident, "PostgresEnum can only be derived for enums");
 
-        t.append(format!("
-            {0}: row.get(\"{0}\"),", ident));
+            return errors.finish().map(|()| tokens);
+        },
+    };
+
+    let container = parse_container_attr(ast, &mut errors);
+
+    for attr in container.table_name.iter().chain(&container.schema).chain(&container.tag) {
+        errors.push(
+            attr,
+            "pg_mapper container attributes `table`, `schema` and `tag` do not apply to PostgresEnum",
+        );
     }
 
-    t.append("
+    if let Some(ref unquoted) = container.unquoted {
+        errors.push(
+            unquoted,
+            "pg_mapper container attribute `unquoted` does not apply to PostgresEnum",
+        );
+    }
+
+    for variant in variants {
+        if let Fields::Named(_) | Fields::Unnamed(_) = variant.fields {
+            errors.push(
+                &variant.fields,
+                format!("PostgresEnum variant `{}` must be a unit variant", variant.ident),
+            );
         }
     }
-}");
-}
 
-#[cfg(feature = "postgres-support")]
-fn impl_from_borrowed_row(t: &mut Tokens, struct_ident: &Ident, fields: &Fields) {
-    t.append(format!("
-impl<'a> From<&'a ::postgres::rows::Row<'a>> for {struct_name} {{
-    fn from(row: &::postgres::rows::Row<'a>) -> Self {{
-        Self {{", struct_name=struct_ident));
+    let type_name = match container.type_name {
+        Some(ref type_name) => type_name.value(),
+        None => infer_type_name(&ast.ident),
+    };
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    #[allow(unused_variables)]
+    let variants = parse_variants(
+        &ast.ident,
+        variants,
+        container.rename_all,
+        &ast.generics,
+        &mut errors,
+    );
 
-        t.append(format!("
-            {0}: row.get(\"{0}\"),", ident));
+    errors.finish()?;
+
+    impl_sql_create_type(&mut tokens, ast, &variants, &type_name);
+
+    #[cfg(feature = "postgres-support")]
+    {
+        impl_enum_sql(&mut tokens, ast, &variants, &type_name, &quote!(::postgres::types));
     }
 
-    t.append("
-        }
+    #[cfg(all(feature = "tokio-postgres-support", not(feature = "postgres-support")))]
+    {
+        impl_enum_sql(&mut tokens, ast, &variants, &type_name, &quote!(::tokio_postgres::types));
     }
-}");
+
+    Ok(tokens)
 }
 
-#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
-fn impl_postgres_mapper(t: &mut Tokens, struct_ident: &Ident, fields: &Fields, table_name: &str) {
-    t.append(format!("
-impl ::postgres_mapper::FromPostgresRow for {struct_name} {{
-    fn from_postgres_row(row: ::postgres::rows::Row)
-        -> Result<Self, ::postgres_mapper::Error> {{
-        Ok(Self {{", struct_name=struct_ident));
+fn impl_sql_create_type(t: &mut Tokens, ast: &DeriveInput, variants: &[MappedVariant], type_name: &str) {
+    let ImplGenerics { params, self_ty, where_clause } = impl_generics(ast, None, Vec::new());
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    let labels = variants.iter().map(|variant| {
+        format!("'{}'", variant.label.replace('\'', "''"))
+    }).collect::<Vec<String>>().join(", ");
+    let statement = format!("CREATE TYPE {} AS ENUM ({})", quote_ident(type_name), labels);
+
+    t.extend(quote! {
+        impl #params #self_ty #where_clause {
+            /// The `CREATE TYPE` statement creating the Postgres ENUM type this enum
+            /// is mapped to.
+            pub fn sql_create_type() -> ::std::string::String {
+                ::std::string::String::from(#statement)
+            }
+        }
+    });
+}
 
-        t.append(format!("
-            {0}: row.get_opt(\"{0}\").ok_or_else(|| ::postgres_mapper::Error::ColumnNotFound)??,", ident));
-    }
+/// Implements `FromSql` and `ToSql` through `types`, the path of the types
+/// module of the enabled postgres crate.
+///
+/// Both crates re-export the same traits, so they are only implemented once.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn impl_enum_sql(
+    t: &mut Tokens,
+    ast: &DeriveInput,
+    variants: &[MappedVariant],
+    type_name: &str,
+    types: &Tokens,
+) {
+    let enum_ident = &ast.ident;
+    let ImplGenerics { params, self_ty, where_clause } = impl_generics(ast, None, Vec::new());
+    let error = boxed_error();
+
+    let from_arms = variants.iter().map(|variant| {
+        let (label, ident) = (&variant.label, &variant.ident);
+
+        quote!(#label => ::std::result::Result::Ok(#enum_ident::#ident),)
+    });
+
+    let to_arms = variants.iter().map(|variant| {
+        let (label, ident) = (&variant.label, &variant.ident);
+
+        quote!(#enum_ident::#ident => #label,)
+    });
+
+    let labels = variants.iter().map(|variant| &variant.label);
+    let count = variants.len();
+    let to_sql_checked = to_sql_checked(types);
+
+    t.extend(quote! {
+        impl #params #types::FromSql for #self_ty #where_clause {
+            fn from_sql(_: &#types::Type, raw: &[u8])
+                -> ::std::result::Result<Self, #error> {
+                match ::std::str::from_utf8(raw)? {
+                    #(#from_arms)*
+                    label => ::std::result::Result::Err(
+                        ::std::format!("unknown {} label `{}`", #type_name, label).into(),
+                    ),
+                }
+            }
 
-    t.append("
-        })
-    }
+            fn accepts(ty: &#types::Type) -> bool {
+                if ty.name() != #type_name {
+                    return false;
+                }
 
-    fn from_postgres_row_ref(row: &::postgres::rows::Row)
-        -> Result<Self, ::postgres_mapper::Error> {
-        Ok(Self {");
+                match *ty.kind() {
+                    #types::Kind::Enum(ref labels) => {
+                        labels.len() == #count && labels.iter().all(|label| match &**label {
+                            #(#labels => true,)*
+                            _ => false,
+                        })
+                    },
+                    _ => false,
+                }
+            }
+        }
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+        impl #params #types::ToSql for #self_ty #where_clause {
+            fn to_sql(&self, _: &#types::Type, out: &mut ::std::vec::Vec<u8>)
+                -> ::std::result::Result<#types::IsNull, #error> {
+                let label = match *self {
+                    #(#to_arms)*
+                };
 
-        t.append(format!("
-            {0}: row.get_opt(\"{0}\").ok_or_else(|| ::postgres_mapper::Error::ColumnNotFound)??,", ident));
-    }
+                out.extend_from_slice(label.as_bytes());
 
-    t.append("
-        })
-    }");
+                ::std::result::Result::Ok(#types::IsNull::No)
+            }
 
-    t.append(
-    format!(
-    "fn sql_fields() -> String {{")
-    );
+            fn accepts(ty: &#types::Type) -> bool {
+                <Self as #types::FromSql>::accepts(ty)
+            }
 
-    let field_name = fields.iter().map(|field| {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
-        format!("{0}", ident)
-    }).collect::<Vec<String>>().join(", ");
+            #to_sql_checked
+        }
+    });
+}
 
-    t.append(format!("\" {0} \".to_string()", field_name));
+fn impl_composite_derive(ast: &DeriveInput) -> Result<Tokens, Vec<syn::Error>> {
+    #[allow(unused_mut)]
+    let mut tokens = Tokens::new();
+    let mut errors = Errors::default();
 
-    t.append(
-    "}"
-    );
+    let struct_fields = match ast.data {
+        Struct(ref s) => &s.fields,
+        _ => {
+            errors.push(&ast.ident, "PostgresComposite can only be derived for structs");
 
-    t.append(format!(
-    "fn sql_table() -> String {{
-        \" {0} \".to_string()
-    }}"
-    , table_name));
+            return errors.finish().map(|()| tokens);
+        },
+    };
 
-    t.append(
-    format!(
-    "fn sql_table_dot_fields() -> String {{")
-    );
+    let container = parse_container_attr(ast, &mut errors);
 
-    let field_name = fields.iter().map(|field| {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
-        format!("{0}.{1}", table_name, ident)
-    }).collect::<Vec<String>>().join(", ");
+    for attr in container.table_name.iter().chain(&container.schema).chain(&container.tag) {
+        errors.push(
+            attr,
+            "pg_mapper container attributes `table`, `schema` and `tag` do not apply to PostgresComposite",
+        );
+    }
+
+    if let Some(ref unquoted) = container.unquoted {
+        errors.push(
+            unquoted,
+            "pg_mapper container attribute `unquoted` does not apply to PostgresComposite",
+        );
+    }
 
-    t.append(format!("\" {0} \".to_string()", field_name));
+    #[allow(unused_variables)]
+    let type_name = match container.type_name {
+        Some(ref type_name) => type_name.value(),
+        None => infer_type_name(&ast.ident),
+    };
 
-    t.append(
-    "}"
+    #[allow(unused_variables)]
+    let fields = parse_fields(
+        struct_fields,
+        &ast.ident.to_string(),
+        container.rename_all,
+        &ast.generics,
+        &mut errors,
     );
 
-    t.append("
-}");
-}
+    for (field, mapped) in struct_fields.iter().zip(&fields) {
+        if mapped.positional {
+            errors.push(
+                field,
+                format!(
+                    "PostgresComposite field `{}` must declare its attribute's name: \
+                     #[pg_mapper(column = \"...\")]",
+                    mapped.ident
+                ),
+            );
+        }
 
-#[cfg(feature = "tokio-postgres-support")]
-fn impl_tokio_from_row(t: &mut Tokens, struct_ident: &Ident, fields: &Fields) {
-    t.append(format!("
-impl From<::tokio_postgres::rows::Row> for {struct_name} {{
-    fn from(row: ::tokio_postgres::rows::Row) -> Self {{
-        Self {{", struct_name=struct_ident));
+        if mapped.skip || mapped.default.is_set() || mapped.flatten || mapped.conversion.is_some() {
+            errors.push(
+                field,
+                format!(
+                    "PostgresComposite field `{}` only supports the rename and column attributes",
+                    mapped.ident
+                ),
+            );
+        }
+    }
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    errors.finish()?;
 
-        t.append(format!("
-            {0}: row.get(\"{0}\"),", ident));
+    #[cfg(feature = "postgres-support")]
+    {
+        impl_composite_sql(&mut tokens, ast, &fields, &type_name, &quote!(::postgres::types));
     }
 
-    t.append("
-        }
+    #[cfg(all(feature = "tokio-postgres-support", not(feature = "postgres-support")))]
+    {
+        impl_composite_sql(&mut tokens, ast, &fields, &type_name, &quote!(::tokio_postgres::types));
     }
-}");
+
+    Ok(tokens)
 }
 
-#[cfg(feature = "tokio-postgres-support")]
-fn impl_tokio_from_borrowed_row(t: &mut Tokens, struct_ident: &Ident, fields: &Fields) {
-    t.append(format!("
-impl<'a> From<&'a ::tokio_postgres::rows::Row> for {struct_name} {{
-    fn from(row: &'a ::tokio_postgres::rows::Row) -> Self {{
-        Self {{", struct_name=struct_ident));
+/// Implements `FromSql` and `ToSql` through `types` for the binary format of
+/// composite types, matching each field to the attribute of the same name.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn impl_composite_sql(
+    t: &mut Tokens,
+    ast: &DeriveInput,
+    fields: &[MappedField],
+    type_name: &str,
+    types: &Tokens,
+) {
+    let from_sql = quote!(#types::FromSql);
+    let to_sql = quote!(#types::ToSql);
+    let from_generics = impl_generics(ast, None, field_bounds(ast, fields, &from_sql, &Tokens::new()));
+    let to_generics = impl_generics(ast, None, field_bounds(ast, fields, &to_sql, &Tokens::new()));
+    let error = boxed_error();
+
+    // The value read for each field, named by the field's index.
+    let values = (0..fields.len())
+        .map(|index| Ident::new(&format!("value_{}", index), Span::call_site()))
+        .collect::<Vec<Ident>>();
+
+    let from_arms = fields.iter().zip(&values).map(|(field, value)| {
+        let (column, ty) = (&field.column, &field.ty);
+
+        quote! {
+            #column => {
+                #value = ::std::option::Option::Some(
+                    <#ty as #from_sql>::from_sql_nullable(field.type_(), value)?,
+                );
+            },
+        }
+    });
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    let members = fields.iter().zip(&values).map(|(field, value)| {
+        let member = &field.member;
+        let missing = format!("missing attribute {} of composite type {}", field.column, type_name);
 
-        t.append(format!("
-            {0}: row.get(\"{0}\"),", ident));
-    }
+        quote!(#member: #value.ok_or(#missing)?,)
+    });
+
+    let to_arms = fields.iter().map(|field| {
+        let (column, member) = (&field.column, &field.member);
+
+        quote!(#column => #to_sql::to_sql(&self.#member, field.type_(), out)?,)
+    });
+
+    let accepts = |method: &Tokens| {
+        let arms = fields.iter().map(|field| {
+            let (column, ty) = (&field.column, &field.ty);
+
+            quote!(#column => <#ty as #method>::accepts(field.type_()),)
+        });
+        let count = fields.len();
+
+        quote! {
+            fn accepts(ty: &#types::Type) -> bool {
+                if ty.name() != #type_name {
+                    return false;
+                }
 
-    t.append("
+                match *ty.kind() {
+                    #types::Kind::Composite(ref fields) => {
+                        fields.len() == #count && fields.iter().all(|field| match field.name() {
+                            #(#arms)*
+                            _ => false,
+                        })
+                    },
+                    _ => false,
+                }
+            }
         }
-    }
-}");
-}
+    };
 
+    let declared = values.iter();
+    let from_accepts = accepts(&from_sql);
+    let to_accepts = accepts(&to_sql);
+    let to_sql_checked = to_sql_checked(types);
 
-#[cfg(all(feature = "tokio-postgres-support", feature = "postgres-mapper"))]
-fn impl_tokio_postgres_mapper(
-    t: &mut Tokens,
-    struct_ident: &Ident,
-    fields: &Fields,
-    table_name: &str,
-) {
-    t.append(format!("
-impl ::postgres_mapper::FromTokioPostgresRow for {struct_name} {{
-    fn from_tokio_postgres_row(row: ::tokio_postgres::rows::Row)
-        -> Result<Self, ::postgres_mapper::Error> {{
-        Ok(Self {{", struct_name=struct_ident));
+    let ImplGenerics { params, self_ty, where_clause } = from_generics;
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+    t.extend(quote! {
+        impl #params #from_sql for #self_ty #where_clause {
+            fn from_sql(ty: &#types::Type, raw: &[u8])
+                -> ::std::result::Result<Self, #error> {
+                fn read_i32(raw: &mut &[u8]) -> ::std::result::Result<i32, #error> {
+                    if raw.len() < 4 {
+                        return ::std::result::Result::Err("invalid composite value".into());
+                    }
 
-        t.append(format!("
-            {0}: row.try_get(\"{0}\")?.ok_or_else(|| ::postgres_mapper::Error::ColumnNotFound)?,", ident));
-    }
+                    let (bytes, rest) = raw.split_at(4);
+                    *raw = rest;
 
-    t.append("
-        })
-    }
+                    ::std::result::Result::Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                }
 
-    fn from_tokio_postgres_row_ref(row: &::tokio_postgres::rows::Row)
-        -> Result<Self, ::postgres_mapper::Error> {
-        Ok(Self {");
+                let fields = match *ty.kind() {
+                    #types::Kind::Composite(ref fields) => fields,
+                    _ => {
+                        return ::std::result::Result::Err(
+                            ::std::format!("expected a composite type, found {}", ty).into(),
+                        );
+                    },
+                };
+
+                let mut raw = raw;
+
+                if read_i32(&mut raw)? as usize != fields.len() {
+                    return ::std::result::Result::Err(
+                        "invalid number of attributes in composite value".into(),
+                    );
+                }
 
-    for field in fields {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
+                #(let mut #declared = ::std::option::Option::None;)*
 
-        t.append(format!("
-            {0}: row.try_get(\"{0}\")?.ok_or_else(|| ::postgres_mapper::Error::ColumnNotFound)?,", ident));
-    }
+                for field in fields {
+                    read_i32(&mut raw)?;
 
-    t.append("
-        })
-    }");
+                    let len = read_i32(&mut raw)?;
+                    let value = if len < 0 {
+                        ::std::option::Option::None
+                    } else if raw.len() < len as usize {
+                        return ::std::result::Result::Err("invalid composite value".into());
+                    } else {
+                        let (value, rest) = raw.split_at(len as usize);
+                        raw = rest;
 
-    t.append(format!(
-    "fn sql_table() -> String {{
-        \" {0} \".to_string()
-    }}"
-    , table_name));
+                        ::std::option::Option::Some(value)
+                    };
 
-    t.append(
-    format!(
-    "fn sql_fields() -> String {{")
-    );
+                    match field.name() {
+                        #(#from_arms)*
+                        _ => {},
+                    }
+                }
 
-    let field_name = fields.iter().map(|field| {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
-        format!("{0}", ident)
-    }).collect::<Vec<String>>().join(", ");
+                ::std::result::Result::Ok(Self {
+                    #(#members)*
+                })
+            }
+
+            #from_accepts
+        }
+    });
+
+    let ImplGenerics { params, self_ty, where_clause } = to_generics;
+
+    t.extend(quote! {
+        impl #params #to_sql for #self_ty #where_clause {
+            fn to_sql(&self, ty: &#types::Type, out: &mut ::std::vec::Vec<u8>)
+                -> ::std::result::Result<#types::IsNull, #error> {
+                let fields = match *ty.kind() {
+                    #types::Kind::Composite(ref fields) => fields,
+                    _ => {
+                        return ::std::result::Result::Err(
+                            ::std::format!("expected a composite type, found {}", ty).into(),
+                        );
+                    },
+                };
+
+                out.extend_from_slice(&(fields.len() as i32).to_be_bytes());
+
+                for field in fields {
+                    out.extend_from_slice(&field.type_().oid().to_be_bytes());
+
+                    let start = out.len();
+                    out.extend_from_slice(&[0; 4]);
+
+                    let is_null = match field.name() {
+                        #(#to_arms)*
+                        name => {
+                            return ::std::result::Result::Err(::std::format!(
+                                "unknown attribute {} of composite type {}",
+                                name,
+                                #type_name,
+                            ).into());
+                        },
+                    };
+
+                    let len = match is_null {
+                        #types::IsNull::Yes => -1,
+                        #types::IsNull::No => {
+                            let len = out.len() - start - 4;
+
+                            if len > i32::max_value() as usize {
+                                return ::std::result::Result::Err("value too large to transmit".into());
+                            }
+
+                            len as i32
+                        },
+                    };
+
+                    out[start..start + 4].copy_from_slice(&len.to_be_bytes());
+                }
+
+                ::std::result::Result::Ok(#types::IsNull::No)
+            }
+
+            #to_accepts
+
+            #to_sql_checked
+        }
+    });
+}
+
+/// The `to_sql_checked` method of a `ToSql` implementation through `types`.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn to_sql_checked(types: &Tokens) -> Tokens {
+    let error = boxed_error();
+
+    quote! {
+        fn to_sql_checked(&self, ty: &#types::Type, out: &mut ::std::vec::Vec<u8>)
+            -> ::std::result::Result<#types::IsNull, #error> {
+            if !<Self as #types::ToSql>::accepts(ty) {
+                return ::std::result::Result::Err(
+                    ::std::boxed::Box::new(#types::WrongType::new(ty.clone())),
+                );
+            }
+
+            self.to_sql(ty, out)
+        }
+    }
+}
 
-    t.append(format!("\" {0} \".to_string()", field_name));
+/// The error type returned by conversions between Rust and Postgres values.
+#[allow(dead_code)]
+fn boxed_error() -> Tokens {
+    quote!(::std::boxed::Box<dyn Send + Sync + ::std::error::Error>)
+}
+
+/// The lifetime of the row borrowed by the implemented trait.
+#[allow(dead_code)]
+fn row_lifetime() -> syn::Lifetime {
+    syn::Lifetime::new("'__row", Span::call_site())
+}
+
+#[cfg(feature = "postgres-support")]
+fn impl_from_row(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, Some(row_lifetime()), postgres_bounds(ast, mapped));
+
+    t.extend(quote! {
+        impl #params ::std::convert::From<::postgres::rows::Row<'__row>> for #self_ty #where_clause {
+            fn from(row: ::postgres::rows::Row<'__row>) -> Self {
+                <Self as ::std::convert::From<&::postgres::rows::Row>>::from(&row)
+            }
+        }
+    });
+}
 
-    t.append(
-    "}"
+#[cfg(feature = "postgres-support")]
+fn impl_from_borrowed_row(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, Some(row_lifetime()), postgres_bounds(ast, mapped));
+    let construct = construct(
+        &ast.ident,
+        mapped,
+        postgres_get,
+        postgres_get_tag(mapped),
+        unknown_tag_or_panic(mapped),
     );
 
-    t.append(
-    format!(
-    "fn sql_table_dot_fields() -> String {{")
+    t.extend(quote! {
+        impl #params ::std::convert::From<&'__row ::postgres::rows::Row<'__row>> for #self_ty
+            #where_clause
+        {
+            fn from(row: &::postgres::rows::Row<'__row>) -> Self {
+                #construct
+            }
+        }
+    });
+}
+
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn impl_postgres_mapper(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let mapper = quote!(::postgres_mapper::FromPostgresRow);
+    let ImplGenerics { params, self_ty, where_clause } = impl_generics(
+        ast,
+        None,
+        mapped_bounds(ast, mapped, &quote!(::postgres::types::FromSql), &mapper),
+    );
+    let construct = construct(
+        &ast.ident,
+        mapped,
+        postgres_get_opt,
+        postgres_get_opt_tag(&ast.ident, mapped),
+        unknown_tag(mapped),
     );
+    let construct_all = construct_all(
+        &ast.ident,
+        mapped,
+        postgres_get_all,
+        postgres_get_opt_tag(&ast.ident, mapped),
+        unknown_tag(mapped),
+    );
+    let check_prefix = positional_prefix(&ast.ident, mapped);
 
-    let field_name = fields.iter().map(|field| {
-        let ident = field.ident.clone().expect("Expected structfield identifier");
-        format!("{0}.{1}", table_name, ident)
-    }).collect::<Vec<String>>().join(", ");
+    t.extend(quote! {
+        impl #params #mapper for #self_ty #where_clause {
+            fn from_postgres_row(row: ::postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_postgres_row_ref(&row)
+            }
+
+            fn from_postgres_row_ref(row: &::postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_postgres_row_prefixed(row, "")
+            }
+
+            fn from_postgres_row_prefixed(row: &::postgres::rows::Row, prefix: &str)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                #check_prefix
+
+                ::std::result::Result::Ok(#construct)
+            }
 
-    t.append(format!("\" {0} \".to_string()", field_name));
+            fn from_postgres_row_all(row: &::postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_postgres_row_all_prefixed(row, "")
+            }
+
+            #[allow(clippy::needless_question_mark, clippy::result_large_err)]
+            fn from_postgres_row_all_prefixed(row: &::postgres::rows::Row, prefix: &str)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                let mut errors = ::std::vec::Vec::new();
+                let value = ::postgres_mapper::collect_error(
+                    (|| -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                        #check_prefix
+
+                        ::std::result::Result::Ok(#construct_all)
+                    })(),
+                    &mut errors,
+                );
+
+                value.ok_or_else(|| ::postgres_mapper::Error::Multiple(errors))
+            }
+        }
+    });
+}
+
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn impl_postgres_borrowed_mapper(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let row = row_lifetime();
+    let mut bounds = ast.generics.lifetimes()
+        .map(|lifetime| {
+            let lifetime = &lifetime.lifetime;
 
-    t.append(
-    "}"
+            parse_quote!(#row: #lifetime)
+        })
+        .collect::<Vec<syn::WherePredicate>>();
+
+    bounds.extend(mapped_bounds(
+        ast,
+        mapped,
+        &quote!(::postgres::types::FromSql),
+        &quote!(::postgres_mapper::FromPostgresRowBorrowed<#row>),
+    ));
+
+    let ImplGenerics { params, self_ty, where_clause } = impl_generics(ast, Some(row), bounds);
+    let construct = construct(
+        &ast.ident,
+        mapped,
+        postgres_get_borrowed,
+        postgres_get_opt_tag(&ast.ident, mapped),
+        unknown_tag(mapped),
     );
+    let check_prefix = positional_prefix(&ast.ident, mapped);
+
+    t.extend(quote! {
+        impl #params ::postgres_mapper::FromPostgresRowBorrowed<'__row> for #self_ty #where_clause {
+            fn from_postgres_row_borrowed(row: &'__row ::postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as ::postgres_mapper::FromPostgresRowBorrowed<'__row>>
+                    ::from_postgres_row_borrowed_prefixed(row, "")
+            }
+
+            fn from_postgres_row_borrowed_prefixed(row: &'__row ::postgres::rows::Row, prefix: &str)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                #check_prefix
 
-    t.append("
-}");
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+    });
 }
 
-fn get_mapper_meta_items(attr: &syn::Attribute) -> Option<Vec<syn::NestedMeta>> {
-    if attr.path.segments.len() == 1 && attr.path.segments[0].ident == "pg_mapper" {
-        match attr.interpret_meta() {
-            Some(List(ref meta)) => Some(meta.nested.iter().cloned().collect()),
-            _ => {
-                panic!("declare table name: #[pg_mapper(table = \"foo\")]");
+#[cfg(feature = "tokio-postgres-support")]
+fn impl_tokio_from_row(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, None, tokio_bounds(ast, mapped));
+
+    t.extend(quote! {
+        impl #params ::std::convert::From<::tokio_postgres::rows::Row> for #self_ty #where_clause {
+            fn from(row: ::tokio_postgres::rows::Row) -> Self {
+                <Self as ::std::convert::From<&::tokio_postgres::rows::Row>>::from(&row)
             }
         }
-    } else {
-        None
-    }
+    });
 }
 
-fn get_lit_str<'a>(
-    attr_name: &Ident,
-    meta_item_name: &Ident,
-    lit: &'a syn::Lit,
-) -> Result<&'a syn::LitStr, ()> {
-    if let syn::Lit::Str(ref lit) = *lit {
-        Ok(lit)
-    } else {
-        panic!(format!(
-            "expected pg_mapper {} attribute to be a string: `{} = \"...\"`",
-            attr_name, meta_item_name
-        ));
-        #[allow(unreachable_code)]
-        Err(())
-    }
+#[cfg(feature = "tokio-postgres-support")]
+fn impl_tokio_from_borrowed_row(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, Some(row_lifetime()), tokio_bounds(ast, mapped));
+    let construct = construct(
+        &ast.ident,
+        mapped,
+        tokio_get,
+        tokio_get_tag(mapped),
+        unknown_tag_or_panic(mapped),
+    );
+
+    t.extend(quote! {
+        impl #params ::std::convert::From<&'__row ::tokio_postgres::rows::Row> for #self_ty
+            #where_clause
+        {
+            fn from(row: &'__row ::tokio_postgres::rows::Row) -> Self {
+                #construct
+            }
+        }
+    });
 }
 
-fn parse_table_attr(ast: &DeriveInput) -> String {
-    // Parse `#[pg_mapper(table = "foo")]`
-    let mut table_name: Option<String> = None;
+#[cfg(all(feature = "tokio-postgres-support", feature = "postgres-mapper"))]
+fn impl_tokio_postgres_mapper(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped) {
+    let mapper = quote!(::postgres_mapper::FromTokioPostgresRow);
+    let ImplGenerics { params, self_ty, where_clause } = impl_generics(
+        ast,
+        None,
+        mapped_bounds(ast, mapped, &quote!(::tokio_postgres::types::FromSql), &mapper),
+    );
+    let construct = construct(
+        &ast.ident,
+        mapped,
+        tokio_try_get,
+        tokio_try_get_tag(&ast.ident, mapped),
+        unknown_tag(mapped),
+    );
+    let construct_all = construct_all(
+        &ast.ident,
+        mapped,
+        tokio_try_get_all,
+        tokio_try_get_tag(&ast.ident, mapped),
+        unknown_tag(mapped),
+    );
+    let check_prefix = positional_prefix(&ast.ident, mapped);
 
-    for meta_items in ast.attrs.iter().filter_map(get_mapper_meta_items) {
+    t.extend(quote! {
+        impl #params #mapper for #self_ty #where_clause {
+            fn from_tokio_postgres_row(row: ::tokio_postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_tokio_postgres_row_ref(&row)
+            }
 
-        for meta_item in meta_items {
-            match meta_item {
-                // Parse `#[pg_mapper(table = "foo")]`
-                Meta(NameValue(ref m)) if m.ident == "table" => {
-                    if let Ok(s) = get_lit_str(&m.ident, &m.ident, &m.lit) {
-                        table_name = Some(s.value());
-                    }
-                }
+            fn from_tokio_postgres_row_ref(row: &::tokio_postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_tokio_postgres_row_prefixed(row, "")
+            }
 
-                Meta(ref meta_item) => {
-                    panic!(format!(
-                        "unknown pg_mapper container attribute `{}`",
-                        meta_item.name()
-                    ))
-                }
+            fn from_tokio_postgres_row_prefixed(row: &::tokio_postgres::rows::Row, prefix: &str)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                #check_prefix
 
-                Literal(_) => {
-                    panic!("unexpected literal in pg_mapper container attribute");
-                }
+                ::std::result::Result::Ok(#construct)
+            }
+
+            fn from_tokio_postgres_row_all(row: &::tokio_postgres::rows::Row)
+                -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                <Self as #mapper>::from_tokio_postgres_row_all_prefixed(row, "")
+            }
+
+            #[allow(clippy::needless_question_mark, clippy::result_large_err)]
+            fn from_tokio_postgres_row_all_prefixed(
+                row: &::tokio_postgres::rows::Row,
+                prefix: &str,
+            ) -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                let mut errors = ::std::vec::Vec::new();
+                let value = ::postgres_mapper::collect_error(
+                    (|| -> ::std::result::Result<Self, ::postgres_mapper::Error> {
+                        #check_prefix
+
+                        ::std::result::Result::Ok(#construct_all)
+                    })(),
+                    &mut errors,
+                );
+
+                value.ok_or_else(|| ::postgres_mapper::Error::Multiple(errors))
             }
         }
-    }
+    });
+}
+
+/// Implements `PostgresTable`, qualifying the columns listed by the type's own
+/// `PostgresFields` implementation.
+#[cfg(feature = "postgres-mapper")]
+fn impl_postgres_table(
+    t: &mut Tokens,
+    ast: &DeriveInput,
+    mapped: &Mapped,
+    table: &Table,
+    quoting: Quoting,
+) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, None, sql_bounds(ast, mapped));
+
+    let table_name = table.sql(quoting);
+    let sql_table = format!(" {} ", table_name);
+    let schema = match table.schema {
+        Some(ref schema) => {
+            let schema = format!(" {} ", quoting.ident(schema, false));
+
+            quote!(::std::option::Option::Some(::std::string::String::from(#schema)))
+        },
+        None => quote!(::std::option::Option::None),
+    };
+
+    let table_dot_fields = quote! {
+        ::std::format!(" {} ", <Self as ::postgres_mapper::PostgresFields>::sql_columns()
+            .into_iter()
+            .map(|column| ::std::format!("{}.{}", #table_name, column))
+            .collect::<::std::vec::Vec<::std::string::String>>()
+            .join(", "))
+    };
+
+    t.extend(quote! {
+        impl #params ::postgres_mapper::PostgresTable for #self_ty #where_clause {
+            fn sql_table() -> ::std::string::String {
+                ::std::string::String::from(#sql_table)
+            }
+
+            fn sql_schema() -> ::std::option::Option<::std::string::String> {
+                #schema
+            }
 
-    table_name.expect("declare table name: #[pg_mapper(table = \"foo\")]")
+            fn sql_table_dot_fields() -> ::std::string::String {
+                #table_dot_fields
+            }
+        }
+    });
 }
 
+#[cfg(feature = "postgres-mapper")]
+fn impl_postgres_fields(t: &mut Tokens, ast: &DeriveInput, mapped: &Mapped, quoting: Quoting) {
+    let ImplGenerics { params, self_ty, where_clause } =
+        impl_generics(ast, None, sql_bounds(ast, mapped));
+
+    let columns = mapped_sql(mapped, |fields| sql_columns(fields, quoting), |column| {
+        let column = quoting.ident(column, false);
+
+        quote!(::std::string::String::from(#column))
+    });
+
+    let columns_aliased = mapped_sql(
+        mapped,
+        |fields| sql_columns_aliased(fields, quoting),
+        |column| aliased_column(column, false, quoting),
+    );
+
+    t.extend(quote! {
+        impl #params ::postgres_mapper::PostgresFields for #self_ty #where_clause {
+            fn sql_fields() -> ::std::string::String {
+                ::std::format!(" {} ", <Self as ::postgres_mapper::PostgresFields>::sql_columns()
+                    .join(", "))
+            }
+
+            fn sql_fields_aliased(alias: &str, prefix: &str) -> ::std::string::String {
+                ::std::format!(
+                    " {} ",
+                    <Self as ::postgres_mapper::PostgresFields>::sql_columns_aliased(alias, prefix)
+                        .join(", "),
+                )
+            }
+
+            fn sql_columns() -> ::std::vec::Vec<::std::string::String> {
+                #columns
+            }
+
+            fn sql_columns_aliased(alias: &str, prefix: &str)
+                -> ::std::vec::Vec<::std::string::String> {
+                #columns_aliased
+            }
+        }
+    });
+}
+
+/// The generics of an impl for the derived type.
+struct ImplGenerics {
+    /// The impl's parameters, as `<...>`.
+    params: Tokens,
+    /// The derived type along with its generic arguments.
+    self_ty: Tokens,
+    /// The impl's where clause.
+    where_clause: Tokens,
+}
+
+/// Builds the generics of an impl for the derived type, adding the
+/// `lifetime` parameter used by the implemented trait, if any, and the
+/// `bounds` needed by its fields.
+#[allow(dead_code)]
+fn impl_generics(
+    ast: &DeriveInput,
+    lifetime: Option<syn::Lifetime>,
+    bounds: Vec<syn::WherePredicate>,
+) -> ImplGenerics {
+    let mut generics = ast.generics.clone();
+
+    if let Some(lifetime) = lifetime {
+        generics.params.insert(0, syn::GenericParam::Lifetime(syn::LifetimeDef::new(lifetime)));
+    }
+
+    generics.make_where_clause().predicates.extend(bounds);
+
+    let (params, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let ident = &ast.ident;
+
+    ImplGenerics {
+        params: params.into_token_stream(),
+        self_ty: quote!(#ident #ty_generics),
+        where_clause: where_clause.into_token_stream(),
+    }
+}
+
+/// Where predicates bounding the type of each field using a type parameter
+/// of the derived type by the traits reading it: `from_sql`, the path of
+/// `FromSql` or `ToSql`, and `flatten`, the trait of flattened fields.
+///
+/// The types of fields converted through `with` or `from_sql` are left to be
+/// bounded by the derived type itself.
+#[allow(dead_code)]
+fn field_bounds<'a, I>(
+    ast: &DeriveInput,
+    fields: I,
+    from_sql: &Tokens,
+    flatten: &Tokens,
+) -> Vec<syn::WherePredicate>
+    where I: IntoIterator<Item = &'a MappedField>,
+{
+    let params = ast.generics.type_params()
+        .map(|param| param.ident.to_string())
+        .collect::<Vec<String>>();
+    let error = boxed_error();
+
+    let mut bounds = Vec::new();
+
+    for field in fields {
+        if !mentions(&field.ty, &params) {
+            continue;
+        }
+
+        let ty = &field.ty;
+
+        // Skipped fields without a function fall back to `Default` too.
+        let uses_default = match field.default {
+            FieldDefault::Default => true,
+            FieldDefault::None => field.skip,
+            FieldDefault::Path(_) => false,
+        };
+
+        if uses_default {
+            bounds.push(parse_quote!(#ty: ::std::default::Default));
+        }
+
+        if field.skip {
+            continue;
+        }
+
+        match field.conversion {
+            _ if field.flatten => bounds.push(parse_quote!(#ty: #flatten)),
+            None => bounds.push(parse_quote!(#ty: #from_sql)),
+            Some(Conversion::TryFrom(ref sql_type)) => {
+                let ty = option_inner(ty).unwrap_or(ty);
+
+                bounds.push(parse_quote!(#ty: ::std::convert::TryFrom<#sql_type>));
+                bounds.push(parse_quote!(
+                    <#ty as ::std::convert::TryFrom<#sql_type>>::Error:
+                        ::std::convert::Into<#error>
+                ));
+            },
+            Some(Conversion::Json) => {
+                bounds.push(parse_quote!(#ty: ::postgres_mapper::DeserializeOwned));
+            },
+            Some(Conversion::FromSql(_)) => {},
+        }
+    }
+
+    bounds
+}
+
+/// The bounds of the fields of every variant of a mapped type.
+#[allow(dead_code)]
+fn mapped_bounds(
+    ast: &DeriveInput,
+    mapped: &Mapped,
+    from_sql: &Tokens,
+    flatten: &Tokens,
+) -> Vec<syn::WherePredicate> {
+    field_bounds(ast, mapped.fields(), from_sql, flatten)
+}
+
+/// Where predicates bounding the type of each flattened field using a type
+/// parameter of the derived type by `PostgresFields`, which lists its columns
+/// in the SQL helpers.
+#[cfg(feature = "postgres-mapper")]
+fn sql_bounds(ast: &DeriveInput, mapped: &Mapped) -> Vec<syn::WherePredicate> {
+    let params = ast.generics.type_params()
+        .map(|param| param.ident.to_string())
+        .collect::<Vec<String>>();
+
+    mapped.fields().into_iter()
+        .filter(|field| field.flatten && !field.skip && mentions(&field.ty, &params))
+        .map(|field| {
+            let ty = &field.ty;
+
+            parse_quote!(#ty: ::postgres_mapper::PostgresFields)
+        })
+        .collect()
+}
+
+/// The bounds of the panicking `postgres` conversions.
+#[cfg(feature = "postgres-support")]
+fn postgres_bounds(ast: &DeriveInput, mapped: &Mapped) -> Vec<syn::WherePredicate> {
+    mapped_bounds(
+        ast,
+        mapped,
+        &quote!(::postgres::types::FromSql),
+        &quote!(for<'r> ::std::convert::From<&'r ::postgres::rows::Row<'r>>),
+    )
+}
+
+/// The bounds of the panicking `tokio-postgres` conversions.
+#[cfg(feature = "tokio-postgres-support")]
+fn tokio_bounds(ast: &DeriveInput, mapped: &Mapped) -> Vec<syn::WherePredicate> {
+    mapped_bounds(
+        ast,
+        mapped,
+        &quote!(::tokio_postgres::types::FromSql),
+        &quote!(for<'r> ::std::convert::From<&'r ::tokio_postgres::rows::Row>),
+    )
+}
+
+/// Expression constructing the mapped type, reading each field with `get`.
+///
+/// The variant of a tagged enum is chosen by matching `tag`, an expression
+/// reading the tag column as a `String`. Tags matching no variant evaluate
+/// `unknown`, with the tag bound to `tag`.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn construct(
+    ident: &Ident,
+    mapped: &Mapped,
+    get: fn(&MappedField) -> Tokens,
+    tag: Tokens,
+    unknown: Tokens,
+) -> Tokens {
+    construct_with(ident, mapped, tag, unknown, |path, fields| {
+        let values = fields.iter().map(|field| {
+            let member = &field.member;
+            let value = get(field);
+
+            quote!(#member: #value,)
+        });
+
+        quote!(#path { #(#values)* })
+    })
+}
+
+/// Expression constructing the mapped type like `construct`, but reading
+/// every field before returning the errors of all failing fields as
+/// `postgres_mapper::Error::Multiple`.
+#[cfg(feature = "postgres-mapper")]
+fn construct_all(
+    ident: &Ident,
+    mapped: &Mapped,
+    get: fn(&MappedField) -> Tokens,
+    tag: Tokens,
+    unknown: Tokens,
+) -> Tokens {
+    construct_with(ident, mapped, tag, unknown, |path, fields| {
+        let locals = (0..fields.len())
+            .map(|index| Ident::new(&format!("value_{}", index), Span::call_site()))
+            .collect::<Vec<Ident>>();
+        let values = fields.iter().map(get);
+        let declared = locals.iter();
+        let members = fields.iter().map(|field| &field.member);
+        let read = locals.iter();
+
+        quote! {{
+            let mut errors = ::std::vec::Vec::new();
+            #(
+                let #declared = ::postgres_mapper::collect_error(
+                    (|| -> ::std::result::Result<_, ::postgres_mapper::Error> {
+                        ::std::result::Result::Ok(#values)
+                    })(),
+                    &mut errors,
+                );
+            )*
+
+            if !errors.is_empty() {
+                return ::std::result::Result::Err(::postgres_mapper::Error::Multiple(errors));
+            }
+
+            #path { #(#members: #read.unwrap(),)* }
+        }}
+    })
+}
+
+/// Expression constructing the mapped type through `construct_fields`,
+/// building a struct or variant at `path` from its fields.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn construct_with<F>(
+    ident: &Ident,
+    mapped: &Mapped,
+    tag: Tokens,
+    unknown: Tokens,
+    construct_fields: F,
+) -> Tokens
+    where F: Fn(Tokens, &[MappedField]) -> Tokens,
+{
+    match *mapped {
+        Mapped::Struct(ref fields) => construct_fields(quote!(Self), fields),
+        Mapped::Tagged { ref variants, .. } => {
+            let arms = variants.iter().map(|variant| {
+                let (label, variant_ident) = (&variant.label, &variant.ident);
+                let value = construct_fields(quote!(#ident::#variant_ident), &variant.fields);
+
+                quote!(#label => #value,)
+            });
+
+            quote! {
+                match &*#tag {
+                    #(#arms)*
+                    tag => #unknown,
+                }
+            }
+        },
+    }
+}
+
+/// Expression building a SQL helper's column list through `sql`.
+///
+/// Tagged enums list the tag column, as built by `tag`, followed by the
+/// columns of every variant, each listed once.
+#[cfg(feature = "postgres-mapper")]
+fn mapped_sql<F, T>(mapped: &Mapped, sql: F, tag: T) -> Tokens
+    where F: Fn(&[MappedField]) -> Tokens,
+          T: Fn(&str) -> Tokens,
+{
+    match *mapped {
+        Mapped::Struct(ref fields) => sql(fields),
+        Mapped::Tagged { ref column, ref variants } => {
+            let tag = tag(column);
+            let parts = Some(quote!(::std::vec![#tag])).into_iter()
+                .chain(variants.iter().map(|variant| sql(&variant.fields)));
+
+            quote!(::postgres_mapper::merge_columns(&[#(#parts),*]))
+        },
+    }
+}
+
+/// Expression building the `sql_columns()` list.
+///
+/// Flattened fields expand to the columns of their own type, which are only
+/// known at runtime through its `PostgresFields` implementation.
+#[cfg(feature = "postgres-mapper")]
+fn sql_columns(fields: &[MappedField], quoting: Quoting) -> Tokens {
+    concat_columns(fields.iter().filter(|field| !field.skip).map(|field| {
+        if field.flatten {
+            let ty = &field.ty;
+
+            quote!(<#ty as ::postgres_mapper::PostgresFields>::sql_columns())
+        } else {
+            let column = field.sql_column(quoting);
+
+            quote!(::std::vec![::std::string::String::from(#column)])
+        }
+    }))
+}
+
+/// Expression building the `sql_columns_aliased()` list from its `alias` and
+/// `prefix` arguments.
+#[cfg(feature = "postgres-mapper")]
+fn sql_columns_aliased(fields: &[MappedField], quoting: Quoting) -> Tokens {
+    concat_columns(fields.iter().filter(|field| !field.skip).map(|field| {
+        if field.flatten {
+            let ty = &field.ty;
+
+            quote!(<#ty as ::postgres_mapper::PostgresFields>::sql_columns_aliased(alias, prefix))
+        } else {
+            let column = aliased_column(&field.column, field.raw, quoting);
+
+            quote!(::std::vec![#column])
+        }
+    }))
+}
+
+/// Expression selecting `column` from the `alias` argument's table, named by
+/// the column prefixed with the `prefix` argument.
+#[cfg(feature = "postgres-mapper")]
+fn aliased_column(column: &str, raw: bool, quoting: Quoting) -> Tokens {
+    let sql_column = quoting.ident(column, raw);
+
+    match quoting {
+        Quoting::All => {
+            let column = column.replace('"', "\"\"");
+
+            quote! {
+                ::std::format!(
+                    "{0}.{1} AS \"{2}{3}\"",
+                    alias,
+                    #sql_column,
+                    prefix.replace('"', "\"\""),
+                    #column,
+                )
+            }
+        },
+        Quoting::Reserved => {
+            quote!(::std::format!("{0}.{1} AS {2}{3}", alias, #sql_column, prefix, #column))
+        },
+    }
+}
+
+/// Expression concatenating the column lists built by `parts`.
+#[cfg(feature = "postgres-mapper")]
+fn concat_columns<I>(parts: I) -> Tokens
+    where I: IntoIterator<Item = Tokens>,
+{
+    let parts = parts.into_iter();
+
+    quote!(<[::std::vec::Vec<::std::string::String>]>::concat(&[#(#parts),*]))
+}
+
+/// The fields read by position, whose columns have no name to list in SQL.
+fn unnamed_fields(mapped: &Mapped) -> Vec<&MappedField> {
+    mapped.fields().into_iter()
+        .filter(|field| field.positional && !field.skip && !field.flatten)
+        .collect()
+}
+
+/// Statement returning `Error::PositionalPrefix` from a `*_prefixed` mapper
+/// method called with a non-empty `prefix`, if any field is read by position.
+///
+/// The `*_all_prefixed` methods run it within the closure whose error they
+/// collect, returning it inside `Error::Multiple`.
+#[cfg(all(
+    feature = "postgres-mapper",
+    any(feature = "postgres-support", feature = "tokio-postgres-support"),
+))]
+fn positional_prefix(ident: &Ident, mapped: &Mapped) -> Tokens {
+    if unnamed_fields(mapped).is_empty() {
+        return Tokens::new();
+    }
+
+    let struct_name = ident.to_string();
+
+    quote! {
+        if !prefix.is_empty() {
+            return ::std::result::Result::Err(::postgres_mapper::Error::PositionalPrefix {
+                struct_name: #struct_name,
+                prefix: ::std::string::String::from(prefix),
+            });
+        }
+    }
+}
+
+/// Expression looking up a field's column, with the `prefix` argument of the
+/// `*_prefixed` mapper methods prepended.
+///
+/// Positional fields are looked up by index, as their mapper methods refuse
+/// a prefix through `positional_prefix`.
+#[cfg(feature = "postgres-mapper")]
+fn prefixed_column(field: &MappedField) -> Tokens {
+    if field.positional {
+        return column_index(field);
+    }
+
+    let column = &field.column;
+
+    quote!(&*::postgres_mapper::prefix_column(prefix, #column))
+}
+
+/// Expression looking up a field's column, by name or by index for
+/// positional fields.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn column_index(field: &MappedField) -> Tokens {
+    match field.member {
+        syn::Member::Unnamed(ref index) if field.positional => {
+            let index = index.index as usize;
+
+            quote!(#index)
+        },
+        _ => {
+            let column = &field.column;
+
+            quote!(#column)
+        },
+    }
+}
+
+/// The type a field's column is read as: the field's own type, the type it
+/// is converted from through `TryFrom` (as an `Option` for `Option` fields),
+/// or `_` to infer it from the conversion.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn column_type(field: &MappedField) -> Tokens {
+    match field.conversion {
+        None => field.ty.clone().into_token_stream(),
+        Some(Conversion::TryFrom(ref sql_type)) if option_inner(&field.ty).is_some() => {
+            quote!(::std::option::Option<#sql_type>)
+        },
+        Some(Conversion::TryFrom(ref sql_type)) => sql_type.clone().into_token_stream(),
+        Some(Conversion::FromSql(_)) | Some(Conversion::Json) => quote!(_),
+    }
+}
+
+/// Expression reading the tag column of a tagged enum from a `postgres` row,
+/// panicking on failure.
+#[cfg(feature = "postgres-support")]
+fn postgres_get_tag(mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => {
+            quote!(row.get::<_, ::std::string::String>(#column))
+        },
+    }
+}
+
+/// Expression reading the tag column of a tagged enum from a `postgres` row,
+/// returning a `postgres_mapper::Error` on failure.
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn postgres_get_opt_tag(ident: &Ident, mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => {
+            let struct_name = ident.to_string();
+
+            quote! {
+                ::postgres_mapper::get_postgres::<_, ::std::string::String>(
+                    row,
+                    &*::postgres_mapper::prefix_column(prefix, #column),
+                    #struct_name,
+                    ::std::option::Option::None,
+                )?
+            }
+        },
+    }
+}
+
+/// Expression reading the tag column of a tagged enum from a `tokio-postgres`
+/// row, panicking on failure.
+#[cfg(feature = "tokio-postgres-support")]
+fn tokio_get_tag(mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => {
+            quote!(row.get::<_, ::std::string::String>(#column))
+        },
+    }
+}
+
+/// Expression reading the tag column of a tagged enum from a `tokio-postgres`
+/// row, returning a `postgres_mapper::Error` on failure.
+#[cfg(all(feature = "tokio-postgres-support", feature = "postgres-mapper"))]
+fn tokio_try_get_tag(ident: &Ident, mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => {
+            let struct_name = ident.to_string();
+
+            quote! {
+                ::postgres_mapper::get_tokio_postgres::<_, ::std::string::String>(
+                    row,
+                    &*::postgres_mapper::prefix_column(prefix, #column),
+                    #struct_name,
+                    ::std::option::Option::None,
+                )?
+            }
+        },
+    }
+}
+
+/// Expression reading a field's column through `get`, the path of a
+/// `postgres_mapper` getter taking the field's name as `name`, and converting
+/// it, falling back to the field's default when the column is missing.
+#[cfg(feature = "postgres-mapper")]
+fn get_or_default(field: &MappedField, get: Tokens, name: Tokens) -> Tokens {
+    let (column, ty, struct_name) = (prefixed_column(field), column_type(field), &field.struct_name);
+    let value = quote!(#get::<_, #ty>(row, #column, #struct_name, #name));
+
+    match field.default {
+        FieldDefault::None => convert(field, quote!(#value?)),
+        ref default => {
+            let converted = convert(field, quote!(value?));
+            let default = default.expr();
+
+            quote! {
+                match #value {
+                    ::std::result::Result::Err(::postgres_mapper::Error::ColumnNotFound { .. }) => {
+                        #default
+                    },
+                    value => #converted,
+                }
+            }
+        },
+    }
+}
+
+/// Expression panicking on an unknown tag, bound to `tag`.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn unknown_tag_or_panic(mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => {
+            quote!(::std::panic!("unknown tag `{}` in column {}", tag, #column))
+        },
+    }
+}
+
+/// Expression returning `postgres_mapper::Error::UnknownTag` for an unknown
+/// tag, bound to `tag`.
+#[cfg(feature = "postgres-mapper")]
+fn unknown_tag(mapped: &Mapped) -> Tokens {
+    match *mapped {
+        Mapped::Struct(_) => Tokens::new(),
+        Mapped::Tagged { ref column, .. } => quote! {
+            return ::std::result::Result::Err(::postgres_mapper::Error::UnknownTag {
+                column: ::postgres_mapper::prefix_column(prefix, #column).into_owned(),
+                tag: tag.to_owned(),
+            })
+        },
+    }
+}
+
+/// Expression retrieving a field's value from a `postgres` row, panicking on
+/// failure.
+#[cfg(feature = "postgres-support")]
+fn postgres_get(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote!(::std::convert::From::from(row));
+    }
+
+    let (column, ty) = (column_index(field), column_type(field));
+
+    match (field.skip, &field.default) {
+        (true, default) => default.expr(),
+        (false, &FieldDefault::None) => {
+            convert_or_panic(field, quote!(row.get::<_, #ty>(#column)))
+        },
+        (false, default) => {
+            let value = convert_or_panic(field, quote!(value.unwrap()));
+            let default = default.expr();
+
+            quote! {
+                match row.get_opt::<_, #ty>(#column) {
+                    ::std::option::Option::Some(value) => #value,
+                    ::std::option::Option::None => #default,
+                }
+            }
+        },
+    }
+}
+
+/// Expression retrieving a field's value from a `postgres` row, returning a
+/// `postgres_mapper::Error` on failure.
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn postgres_get_opt(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote!(::postgres_mapper::FromPostgresRow::from_postgres_row_prefixed(row, prefix)?);
+    }
+
+    if field.skip {
+        return field.default.expr();
+    }
+
+    let name = &field.ident;
+
+    get_or_default(
+        field,
+        quote!(::postgres_mapper::get_postgres),
+        quote!(::std::option::Option::Some(#name)),
+    )
+}
+
+/// Expression retrieving a field's value from a `postgres` row like
+/// `postgres_get_opt`, collecting the errors of flattened fields.
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn postgres_get_all(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote! {
+            ::postgres_mapper::FromPostgresRow::from_postgres_row_all_prefixed(row, prefix)?
+        };
+    }
+
+    postgres_get_opt(field)
+}
+
+/// Expression retrieving a field's value from a `postgres` row, borrowing it
+/// from the row if the field is borrowed, and returning a
+/// `postgres_mapper::Error` on failure.
+#[cfg(all(feature = "postgres-support", feature = "postgres-mapper"))]
+fn postgres_get_borrowed(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote! {
+            ::postgres_mapper::FromPostgresRowBorrowed::from_postgres_row_borrowed_prefixed(
+                row,
+                prefix,
+            )?
+        };
+    }
+
+    if !field.borrowed {
+        return postgres_get_opt(field);
+    }
+
+    let name = &field.ident;
+
+    get_or_default(field, quote!(::postgres_mapper::get_borrowed), quote!(#name))
+}
+
+/// Expression retrieving a field's value from a `tokio-postgres` row,
+/// panicking on failure.
+#[cfg(feature = "tokio-postgres-support")]
+fn tokio_get(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote!(::std::convert::From::from(row));
+    }
+
+    let (column, ty) = (column_index(field), column_type(field));
+
+    match (field.skip, &field.default) {
+        (true, default) => default.expr(),
+        (false, &FieldDefault::None) => {
+            convert_or_panic(field, quote!(row.get::<_, #ty>(#column)))
+        },
+        (false, default) => {
+            let value = convert_or_panic(field, quote!(value));
+            let default = default.expr();
+
+            quote! {
+                match row.try_get::<_, #ty>(#column).unwrap() {
+                    ::std::option::Option::Some(value) => #value,
+                    ::std::option::Option::None => #default,
+                }
+            }
+        },
+    }
+}
+
+/// Expression retrieving a field's value from a `tokio-postgres` row,
+/// returning a `postgres_mapper::Error` on failure.
+#[cfg(all(feature = "tokio-postgres-support", feature = "postgres-mapper"))]
+fn tokio_try_get(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote! {
+            ::postgres_mapper::FromTokioPostgresRow::from_tokio_postgres_row_prefixed(row, prefix)?
+        };
+    }
+
+    if field.skip {
+        return field.default.expr();
+    }
+
+    let name = &field.ident;
+
+    get_or_default(
+        field,
+        quote!(::postgres_mapper::get_tokio_postgres),
+        quote!(::std::option::Option::Some(#name)),
+    )
+}
+
+/// Expression retrieving a field's value from a `tokio-postgres` row like
+/// `tokio_try_get`, collecting the errors of flattened fields.
+#[cfg(all(feature = "tokio-postgres-support", feature = "postgres-mapper"))]
+fn tokio_try_get_all(field: &MappedField) -> Tokens {
+    if field.flatten {
+        return quote! {
+            ::postgres_mapper::FromTokioPostgresRow::from_tokio_postgres_row_all_prefixed(
+                row,
+                prefix,
+            )?
+        };
+    }
+
+    tokio_try_get(field)
+}
+
+/// Wraps an expression reading a field's column in the field's conversion,
+/// if any, panicking if the conversion fails.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn convert_or_panic(field: &MappedField, value: Tokens) -> Tokens {
+    let column = &field.column;
+
+    match field.conversion {
+        Some(Conversion::Json) => quote! {
+            match ::postgres_mapper::from_json(#value, #column) {
+                ::std::result::Result::Ok(value) => value,
+                ::std::result::Result::Err(err) => ::std::panic!("{}", err),
+            }
+        },
+        Some(ref conversion) => {
+            let converted = conversion.apply(field, value);
+            let error = boxed_error();
+
+            quote! {
+                match #converted {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(err) => {
+                        let err: #error = ::std::convert::Into::into(err);
+                        ::std::panic!("error converting column {}: {}", #column, err)
+                    },
+                }
+            }
+        },
+        None => value,
+    }
+}
+
+/// Wraps an expression reading a field's column in the field's conversion,
+/// if any, returning a `postgres_mapper::Error` if the conversion fails.
+#[cfg(feature = "postgres-mapper")]
+fn convert(field: &MappedField, value: Tokens) -> Tokens {
+    match field.conversion {
+        Some(Conversion::Json) if field.positional => {
+            let column = &field.column;
+
+            quote!(::postgres_mapper::from_json(#value, #column)?)
+        },
+        Some(Conversion::Json) => {
+            let column = prefixed_column(field);
+
+            quote!(::postgres_mapper::from_json(#value, #column)?)
+        },
+        Some(ref conversion) => {
+            let converted = conversion.apply(field, value);
+            let variant = conversion.error_variant();
+            let name = &field.ident;
+
+            quote! {
+                #converted.map_err(|err| ::postgres_mapper::Error::#variant {
+                    field: #name,
+                    error: ::std::convert::Into::into(err),
+                })?
+            }
+        },
+        None => value,
+    }
+}
+
+/// Whether a type mentions any of the given type or lifetime parameters.
+fn mentions(ty: &syn::Type, params: &[String]) -> bool {
+    type_str(ty)
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '\'')
+        .any(|word| params.iter().any(|param| param == word))
+}
+
+/// The type inside an `Option` type, or `None` for other types.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match *ty {
+        syn::Type::Path(syn::TypePath { qself: None, ref path }) => path.segments.last()?,
+        _ => return None,
+    };
+    let segment = segment.into_value();
+
+    match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref generic)
+            if segment.ident == "Option" && generic.args.len() == 1 =>
+        {
+            match generic.args[0] {
+                syn::GenericArgument::Type(ref inner) => Some(inner),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Renders a type back into source code.
+fn type_str(ty: &syn::Type) -> String {
+    ty.into_token_stream().to_string()
+}
+
+/// The name of an identifier, without the `r#` prefix of raw identifiers.
+fn unraw(ident: &Ident) -> String {
+    let ident = ident.to_string();
+
+    match ident.starts_with("r#") {
+        true => ident[2..].to_owned(),
+        false => ident,
+    }
+}
+
+/// Keywords reserved by Postgres, which can't name a column or table unless
+/// quoted.
+#[cfg(feature = "postgres-mapper")]
+const RESERVED_WORDS: &[&str] = &[
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric",
+    "authorization", "binary", "both", "case", "cast", "check", "collate", "collation",
+    "column", "concurrently", "constraint", "create", "cross", "current_catalog",
+    "current_date", "current_role", "current_schema", "current_time", "current_timestamp",
+    "current_user", "default", "deferrable", "desc", "distinct", "do", "else", "end",
+    "except", "false", "fetch", "for", "foreign", "freeze", "from", "full", "grant", "group",
+    "having", "ilike", "in", "initially", "inner", "intersect", "into", "is", "isnull",
+    "join", "lateral", "leading", "left", "like", "limit", "localtime", "localtimestamp",
+    "natural", "not", "notnull", "null", "offset", "on", "only", "or", "order", "outer",
+    "overlaps", "placing", "primary", "references", "returning", "right", "select",
+    "session_user", "similar", "some", "symmetric", "system_user", "table", "tablesample",
+    "then", "to", "trailing", "true", "union", "unique", "user", "using", "variadic",
+    "verbose", "when", "where", "window", "with",
+];
+
+/// Whether a name is a keyword reserved by Postgres.
+#[cfg(feature = "postgres-mapper")]
+fn is_reserved(name: &str) -> bool {
+    RESERVED_WORDS.binary_search(&&*name.to_ascii_lowercase()).is_ok()
+}
+
+/// Quotes an identifier for use in SQL.
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// The table a type deriving `PostgresMapper` is selected from.
+#[allow(dead_code)]
+struct Table {
+    schema: Option<String>,
+    name: String,
+}
+
+#[cfg(feature = "postgres-mapper")]
+impl Table {
+    /// The table's name as written in SQL, qualified by its schema.
+    fn sql(&self, quoting: Quoting) -> String {
+        let name = quoting.ident(&self.name, false);
+
+        match self.schema {
+            Some(ref schema) => format!("{}.{}", quoting.ident(schema, false), name),
+            None => name,
+        }
+    }
+}
+
+/// How table and column names are written in the SQL fragments of a mapped
+/// type.
+#[derive(Clone, Copy)]
+enum Quoting {
+    /// Every name is quoted.
+    All,
+    /// Only reserved words are quoted, through `#[pg_mapper(unquoted)]`.
+    Reserved,
+}
+
+#[cfg(feature = "postgres-mapper")]
+impl Quoting {
+    /// A table or column name as written in SQL. `raw` names, taken from raw
+    /// identifiers, are always quoted.
+    fn ident(self, name: &str, raw: bool) -> String {
+        match self {
+            Quoting::Reserved if !raw && !is_reserved(name) => name.to_owned(),
+            _ => quote_ident(name),
+        }
+    }
+}
+
+/// Errors found in the input of a derive, reported together as compile errors
+/// pointing at the offending attributes and fields.
+#[derive(Default)]
+struct Errors(Vec<syn::Error>);
+
+impl Errors {
+    fn push<T: ToTokens, U: Display>(&mut self, tokens: T, message: U) {
+        self.0.push(syn::Error::new_spanned(tokens, message));
+    }
+
+    fn finish(self) -> Result<(), Vec<syn::Error>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+fn compile_errors(errors: Vec<syn::Error>) -> TokenStream {
+    errors.iter().map(|error| TokenStream::from(error.to_compile_error())).collect()
+}
+
+/// The meta items of every `#[pg_mapper(...)]` attribute.
+fn get_mapper_meta_items(attrs: &[syn::Attribute], errors: &mut Errors) -> Vec<syn::NestedMeta> {
+    let mut meta_items = Vec::new();
+
+    for attr in attrs {
+        if attr.path.segments.len() != 1 || attr.path.segments[0].ident != "pg_mapper" {
+            continue;
+        }
+
+        match attr.parse_meta() {
+            Ok(List(ref meta)) => meta_items.extend(meta.nested.iter().cloned()),
+            Ok(_) => errors.push(attr, "expected pg_mapper attribute to be a list: #[pg_mapper(...)]"),
+            Err(err) => errors.0.push(err),
+        }
+    }
+
+    meta_items
+}
+
+fn get_lit_str<'a>(meta: &'a syn::MetaNameValue, errors: &mut Errors) -> Option<&'a syn::LitStr> {
+    if let syn::Lit::Str(ref lit) = meta.lit {
+        Some(lit)
+    } else {
+        errors.push(
+            &meta.lit,
+            format!(
+                "expected pg_mapper {} attribute to be a string: `{} = \"...\"`",
+                meta.ident, meta.ident
+            ),
+        );
+
+        None
+    }
+}
+
+/// Parses the contents of a string attribute, such as a path or a type,
+/// reporting failures at the string.
+fn parse_lit_str<T: syn::parse::Parse>(lit: &syn::LitStr, errors: &mut Errors) -> Option<T> {
+    match lit.parse() {
+        Ok(value) => Some(value),
+        Err(err) => {
+            errors.push(lit, err);
+
+            None
+        },
+    }
+}
+
+/// Attributes declared on the mapped struct or enum through
+/// `#[pg_mapper(...)]`.
+struct ContainerAttr {
+    table_name: Option<syn::LitStr>,
+    /// The schema qualifying the table.
+    schema: Option<syn::LitStr>,
+    /// The name of the Postgres type of a `PostgresEnum` or
+    /// `PostgresComposite`.
+    type_name: Option<syn::LitStr>,
+    /// The column holding the tag of a mapped enum's variant.
+    tag: Option<syn::LitStr>,
+    rename_all: Option<RenameRule>,
+    /// Set when SQL fragments only quote names that are reserved words.
+    unquoted: Option<Ident>,
+}
+
+fn parse_container_attr(ast: &DeriveInput, errors: &mut Errors) -> ContainerAttr {
+    let mut table_name: Option<syn::LitStr> = None;
+    let mut schema: Option<syn::LitStr> = None;
+    let mut type_name: Option<syn::LitStr> = None;
+    let mut tag: Option<syn::LitStr> = None;
+    let mut rename_all: Option<RenameRule> = None;
+    let mut unquoted: Option<Ident> = None;
+
+    for meta_item in get_mapper_meta_items(&ast.attrs, errors) {
+        match meta_item {
+            // Parse `#[pg_mapper(table = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "table" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    set_container_attr(&mut table_name, s.clone(), "table", m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(table)]`, inferring the table from the type
+            Meta(Word(ref w)) if w == "table" => {
+                let name = syn::LitStr::new(&infer_table_name(&ast.ident), w.span());
+
+                set_container_attr(&mut table_name, name, "table", w, errors);
+            }
+
+            // Parse `#[pg_mapper(schema = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "schema" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    set_container_attr(&mut schema, s.clone(), "schema", m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(type_name = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "type_name" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    set_container_attr(&mut type_name, s.clone(), "type_name", m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(tag = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "tag" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    set_container_attr(&mut tag, s.clone(), "tag", m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(rename_all = "camelCase")]`
+            Meta(NameValue(ref m)) if m.ident == "rename_all" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    if let Some(rule) = RenameRule::from_lit(s, errors) {
+                        set_container_attr(&mut rename_all, rule, "rename_all", m, errors);
+                    }
+                }
+            }
+
+            // Parse `#[pg_mapper(unquoted)]`
+            Meta(Word(ref w)) if w == "unquoted" => {
+                set_container_attr(&mut unquoted, w.clone(), "unquoted", w, errors);
+            }
+
+            Meta(ref meta_item) => {
+                errors.push(
+                    meta_item,
+                    format!("unknown pg_mapper container attribute `{}`", meta_item.name()),
+                );
+            }
+
+            Literal(ref lit) => {
+                errors.push(lit, "unexpected literal in pg_mapper container attribute");
+            }
+        }
+    }
+
+    ContainerAttr {
+        table_name,
+        schema,
+        type_name,
+        tag,
+        rename_all,
+        unquoted,
+    }
+}
+
+/// Stores the value of the container attribute `name`, reporting it if it was
+/// already declared.
+fn set_container_attr<T, U: ToTokens>(
+    slot: &mut Option<T>,
+    value: T,
+    name: &str,
+    meta: U,
+    errors: &mut Errors,
+) {
+    if slot.is_some() {
+        errors.push(
+            meta,
+            format!("pg_mapper container attribute `{}` can only be declared once", name),
+        );
+    }
+
+    *slot = Some(value);
+}
+
+/// The snake_case name of a type, such as `order_status` for `OrderStatus`,
+/// used as the default Postgres type name.
+///
+/// Unlike `rename_all = "snake_case"`, runs of uppercase letters are kept
+/// together as one word, so `HTTPMethod` becomes `http_method`.
+fn infer_type_name(ident: &Ident) -> String {
+    let chars = unraw(ident).chars().collect::<Vec<char>>();
+    let mut name = String::with_capacity(chars.len());
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if i > 0 && ch.is_uppercase() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+
+            if !prev.is_uppercase() || next_lower {
+                name.push('_');
+            }
+        }
+
+        name.extend(ch.to_lowercase());
+    }
+
+    name
+}
+
+/// The snake_case plural of a type's name, such as `order_items` for
+/// `OrderItem`, split into words like `infer_type_name`.
+fn infer_table_name(ident: &Ident) -> String {
+    let name = infer_type_name(ident);
+
+    if name.ends_with('y') && !name[..name.len() - 1].ends_with(|ch| "aeiou".contains(ch)) {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| name.ends_with(suffix)) {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
+    }
+}
+
+/// Casing rule applied to every field's column name, or every variant's
+/// label, through `#[pg_mapper(rename_all = "...")]`.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    /// `lowercase`
+    Lower,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `kebab-case`
+    Kebab,
+}
+
+impl RenameRule {
+    fn from_lit(rule: &syn::LitStr, errors: &mut Errors) -> Option<Self> {
+        match &*rule.value() {
+            "lowercase" => Some(RenameRule::Lower),
+            "camelCase" => Some(RenameRule::Camel),
+            "snake_case" => Some(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(RenameRule::ScreamingSnake),
+            "kebab-case" => Some(RenameRule::Kebab),
+            unknown => {
+                errors.push(
+                    rule,
+                    format!(
+                        "unknown pg_mapper rename_all rule `{}`, expected one of \
+                         `lowercase`, `camelCase`, `snake_case`, \
+                         `SCREAMING_SNAKE_CASE`, `kebab-case`",
+                        unknown
+                    ),
+                );
+
+                None
+            },
+        }
+    }
+
+    /// Applies the rule to a snake_case field name.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower => field.to_ascii_lowercase(),
+            RenameRule::Snake => field.to_owned(),
+            RenameRule::Camel => {
+                let mut camel = String::with_capacity(field.len());
+                let mut capitalize = false;
+
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = !camel.is_empty();
+                    } else if capitalize {
+                        camel.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        camel.push(ch);
+                    }
+                }
+
+                camel
+            },
+            RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Kebab => field.replace('_', "-"),
+        }
+    }
+
+    /// Applies the rule to a PascalCase variant name.
+    ///
+    /// As with serde, every uppercase letter starts a new word, so `HTTPLog`
+    /// becomes `h_t_t_p_log` under `snake_case`.
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            },
+            RenameRule::Snake => {
+                let mut snake = String::with_capacity(variant.len());
+
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+
+                    snake.push(ch.to_ascii_lowercase());
+                }
+
+                snake
+            },
+            RenameRule::ScreamingSnake => {
+                RenameRule::Snake.apply_to_variant(variant).to_ascii_uppercase()
+            },
+            RenameRule::Kebab => RenameRule::Snake.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+}
+
+/// An enum variant along with the label of the Postgres ENUM value, or the
+/// tag, it is mapped to.
+#[allow(dead_code)]
+struct MappedVariant {
+    ident: Ident,
+    label: String,
+    /// The fields of a variant of a tagged enum.
+    fields: Vec<MappedField>,
+}
+
+/// How a type deriving `PostgresMapper` is mapped from a row.
+#[allow(dead_code)]
+enum Mapped {
+    /// A struct, mapped from its fields.
+    Struct(Vec<MappedField>),
+    /// An enum mapping the variant whose tag is held by the tag column.
+    Tagged {
+        column: String,
+        variants: Vec<MappedVariant>,
+    },
+}
+
+impl Mapped {
+    /// The fields of the struct, or of every variant of the enum.
+    fn fields(&self) -> Vec<&MappedField> {
+        match *self {
+            Mapped::Struct(ref fields) => fields.iter().collect(),
+            Mapped::Tagged { ref variants, .. } => {
+                variants.iter().flat_map(|variant| variant.fields.iter()).collect()
+            },
+        }
+    }
+}
+
+fn parse_variants<'a, I>(
+    enum_ident: &Ident,
+    variants: I,
+    rename_all: Option<RenameRule>,
+    generics: &syn::Generics,
+    errors: &mut Errors,
+) -> Vec<MappedVariant>
+    where I: IntoIterator<Item = &'a syn::Variant>,
+{
+    variants.into_iter().map(|variant| {
+        let label = match (parse_variant_attr(variant, errors), rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.apply_to_variant(&unraw(&variant.ident)),
+            (None, None) => unraw(&variant.ident),
+        };
+
+        MappedVariant {
+            ident: variant.ident.clone(),
+            label,
+            fields: parse_fields(
+                &variant.fields,
+                &format!("{}::{}", enum_ident, variant.ident),
+                None,
+                generics,
+                errors,
+            ),
+        }
+    }).collect()
+}
+
+/// Parses the `rename` attribute of an enum variant.
+fn parse_variant_attr(variant: &syn::Variant, errors: &mut Errors) -> Option<String> {
+    let mut rename = None;
+
+    for meta_item in get_mapper_meta_items(&variant.attrs, errors) {
+        match meta_item {
+            // Parse `#[pg_mapper(rename = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "rename" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    rename = Some(s.value());
+                }
+            }
+
+            Meta(ref meta_item) => {
+                errors.push(
+                    meta_item,
+                    format!("unknown pg_mapper variant attribute `{}`", meta_item.name()),
+                );
+            }
+
+            Literal(ref lit) => {
+                errors.push(lit, "unexpected literal in pg_mapper variant attribute");
+            }
+        }
+    }
+
+    rename
+}
+
+/// A struct field along with the name of the column it is mapped from.
+#[allow(dead_code)]
+struct MappedField {
+    /// The field's name, or its index in a tuple struct.
+    ident: String,
+    /// The name of the struct, or `Enum::Variant`, holding the field.
+    struct_name: String,
+    /// The field's name or index, as used to construct the struct.
+    member: syn::Member,
+    ty: syn::Type,
+    /// The column's name, or its index for positional fields.
+    column: String,
+    /// Whether the column is named after a raw identifier, naming a Rust
+    /// keyword, and so always quoted in SQL.
+    raw: bool,
+    /// Whether the field of a tuple struct is read by index, as it declares
+    /// no column name.
+    positional: bool,
+    /// Whether the field's type uses a lifetime parameter, borrowing its value,
+    /// or the values of a flattened type's fields, from the row.
+    borrowed: bool,
+    /// Whether the field is never read from a row.
+    skip: bool,
+    /// The value used when the field is skipped or its column is missing.
+    default: FieldDefault,
+    /// Whether the field's type is mapped from the same row.
+    flatten: bool,
+    /// How the column's value is converted into the field's type.
+    conversion: Option<Conversion>,
+}
+
+#[cfg(feature = "postgres-mapper")]
+impl MappedField {
+    /// The column's name as written in SQL fragments.
+    fn sql_column(&self, quoting: Quoting) -> String {
+        quoting.ident(&self.column, self.raw)
+    }
+}
+
+fn parse_fields(
+    fields: &Fields,
+    struct_name: &str,
+    rename_all: Option<RenameRule>,
+    generics: &syn::Generics,
+    errors: &mut Errors,
+) -> Vec<MappedField> {
+    let lifetimes = generics.lifetimes()
+        .map(|lifetime| lifetime.lifetime.to_string())
+        .collect::<Vec<String>>();
+
+    fields.iter().enumerate().map(|(index, field)| {
+        let (ident, member) = match field.ident {
+            Some(ref ident) => (unraw(ident), syn::Member::Named(ident.clone())),
+            None => (index.to_string(), syn::Member::Unnamed(index.into())),
+        };
+        let attr = parse_field_attr(field, errors);
+
+        if attr.skip && attr.rename.is_some() {
+            errors.push(
+                field,
+                format!("pg_mapper field `{}` can not be both skipped and renamed", ident),
+            );
+        }
+
+        if attr.flatten && (attr.skip || attr.rename.is_some() || attr.default.is_set()) {
+            errors.push(
+                field,
+                format!(
+                    "pg_mapper field `{}` can not combine `flatten` with `skip`, `rename` or \
+                     `default`",
+                    ident
+                ),
+            );
+        }
+
+        if attr.conversion.is_some() && (attr.skip || attr.flatten) {
+            errors.push(
+                field,
+                format!(
+                    "pg_mapper field `{}` can not combine `with`, `from_sql`, `try_from` or \
+                     `json` with `skip` or `flatten`",
+                    ident
+                ),
+            );
+        }
+
+        let positional = field.ident.is_none() && attr.rename.is_none();
+        let raw = match field.ident {
+            Some(ref ident) => attr.rename.is_none() && ident.to_string().starts_with("r#"),
+            None => false,
+        };
+
+        let column = match (attr.rename, rename_all) {
+            (Some(rename), _) => rename,
+            (None, _) if positional => index.to_string(),
+            (None, Some(rule)) => rule.apply_to_field(&ident),
+            (None, None) => ident.clone(),
+        };
+
+        let borrowed = !attr.skip && mentions(&field.ty, &lifetimes);
+
+        MappedField {
+            ident,
+            struct_name: struct_name.to_owned(),
+            member,
+            ty: field.ty.clone(),
+            column,
+            raw,
+            positional,
+            borrowed,
+            skip: attr.skip,
+            default: attr.default,
+            flatten: attr.flatten,
+            conversion: attr.conversion,
+        }
+    }).collect()
+}
+
+/// Attributes declared on a struct field through `#[pg_mapper(...)]`.
+#[derive(Default)]
+struct FieldAttr {
+    rename: Option<String>,
+    skip: bool,
+    default: FieldDefault,
+    flatten: bool,
+    conversion: Option<Conversion>,
+}
+
+/// A conversion from the value read from a column into a field's type.
+enum Conversion {
+    /// A function taking the column's value, from `with` or `from_sql`.
+    FromSql(syn::Path),
+    /// A `TryFrom` implementation from the given column type.
+    TryFrom(syn::Type),
+    /// Deserialization of a `json` or `jsonb` column through `serde`.
+    Json,
+}
+
+impl Conversion {
+    /// Expression converting the column's value into the field's type,
+    /// evaluating to a `Result`.
+    ///
+    /// `try_from` conversions into an `Option` field convert the value inside
+    /// an optional column, leaving `NULL`s as `None`.
+    #[allow(dead_code)]
+    fn apply(&self, field: &MappedField, value: Tokens) -> Tokens {
+        match *self {
+            Conversion::FromSql(ref path) => quote!(#path(#value)),
+            Conversion::TryFrom(ref sql_type) => match option_inner(&field.ty) {
+                Some(ty) => quote! {
+                    ::std::option::Option::transpose(::std::option::Option::map(
+                        #value,
+                        <#ty as ::std::convert::TryFrom<#sql_type>>::try_from,
+                    ))
+                },
+                None => {
+                    let ty = &field.ty;
+
+                    quote!(<#ty as ::std::convert::TryFrom<#sql_type>>::try_from(#value))
+                },
+            },
+            Conversion::Json => unreachable!("json fields are converted by `from_json`"),
+        }
+    }
+
+    /// The `postgres_mapper::Error` variant reporting a failed conversion.
+    #[allow(dead_code)]
+    fn error_variant(&self) -> Ident {
+        let variant = match *self {
+            Conversion::FromSql(_) => "Custom",
+            Conversion::TryFrom(_) => "TryFrom",
+            Conversion::Json => unreachable!("json fields are converted by `from_json`"),
+        };
+
+        Ident::new(variant, Span::call_site())
+    }
+}
+
+/// Where the value of a skipped or missing field comes from.
+#[derive(Default)]
+enum FieldDefault {
+    /// The field must be read from its column.
+    #[default]
+    None,
+    /// `Default::default()`.
+    Default,
+    /// A function returning the value.
+    Path(syn::Path),
+}
+
+impl FieldDefault {
+    fn is_set(&self) -> bool {
+        match *self {
+            FieldDefault::None => false,
+            FieldDefault::Default | FieldDefault::Path(_) => true,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn expr(&self) -> Tokens {
+        match *self {
+            FieldDefault::None | FieldDefault::Default => {
+                quote!(::std::default::Default::default())
+            },
+            FieldDefault::Path(ref path) => quote!(#path()),
+        }
+    }
+}
+
+fn parse_field_attr(field: &syn::Field, errors: &mut Errors) -> FieldAttr {
+    let mut attr = FieldAttr::default();
+
+    for meta_item in get_mapper_meta_items(&field.attrs, errors) {
+        match meta_item {
+            // Parse `#[pg_mapper(rename = "foo")]` and `#[pg_mapper(column = "foo")]`
+            Meta(NameValue(ref m)) if m.ident == "rename" || m.ident == "column" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    if attr.rename.is_some() {
+                        errors.push(
+                            m,
+                            "pg_mapper field attributes `rename` and `column` can only be \
+                             declared once",
+                        );
+                    }
+
+                    attr.rename = Some(s.value());
+                }
+            }
+
+            // Parse `#[pg_mapper(skip)]`
+            Meta(Word(ref w)) if w == "skip" => {
+                attr.skip = true;
+            }
+
+            // Parse `#[pg_mapper(flatten)]`
+            Meta(Word(ref w)) if w == "flatten" => {
+                attr.flatten = true;
+            }
+
+            // Parse `#[pg_mapper(with = "module")]`
+            Meta(NameValue(ref m)) if m.ident == "with" => {
+                if let Some(s) = get_lit_str(m, errors) {
+                    if let Some(mut path) = parse_lit_str::<syn::Path>(s, errors) {
+                        path.segments.push(Ident::new("from_sql", s.span()).into());
+                        set_conversion(&mut attr, Conversion::FromSql(path), m, errors);
+                    }
+                }
+            }
+
+            // Parse `#[pg_mapper(from_sql = "path::to::fn")]`
+            Meta(NameValue(ref m)) if m.ident == "from_sql" => {
+                if let Some(path) = get_lit_str(m, errors).and_then(|s| parse_lit_str(s, errors)) {
+                    set_conversion(&mut attr, Conversion::FromSql(path), m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(try_from = "SqlType")]`
+            Meta(NameValue(ref m)) if m.ident == "try_from" => {
+                if let Some(ty) = get_lit_str(m, errors).and_then(|s| parse_lit_str(s, errors)) {
+                    set_conversion(&mut attr, Conversion::TryFrom(ty), m, errors);
+                }
+            }
+
+            // Parse `#[pg_mapper(json)]`
+            Meta(Word(ref w)) if w == "json" => {
+                if !cfg!(feature = "serde") {
+                    errors.push(
+                        w,
+                        "pg_mapper field attribute `json` requires the `serde` feature of \
+                         postgres-mapper-derive",
+                    );
+                }
+
+                set_conversion(&mut attr, Conversion::Json, w, errors);
+            }
+
+            // Parse `#[pg_mapper(default)]`
+            Meta(Word(ref w)) if w == "default" => {
+                attr.default = FieldDefault::Default;
+            }
+
+            // Parse `#[pg_mapper(default = "path::to::fn")]`
+            Meta(NameValue(ref m)) if m.ident == "default" => {
+                if let Some(path) = get_lit_str(m, errors).and_then(|s| parse_lit_str(s, errors)) {
+                    attr.default = FieldDefault::Path(path);
+                }
+            }
+
+            Meta(ref meta_item) => {
+                errors.push(
+                    meta_item,
+                    format!("unknown pg_mapper field attribute `{}`", meta_item.name()),
+                );
+            }
+
+            Literal(ref lit) => {
+                errors.push(lit, "unexpected literal in pg_mapper field attribute");
+            }
+        }
+    }
+
+    attr
+}
+
+fn set_conversion<T: ToTokens>(
+    attr: &mut FieldAttr,
+    conversion: Conversion,
+    meta: T,
+    errors: &mut Errors,
+) {
+    if attr.conversion.is_some() {
+        errors.push(
+            meta,
+            "pg_mapper field attributes `with`, `from_sql`, `try_from` and `json` can only be \
+             declared once",
+        );
+    }
+
+    attr.conversion = Some(conversion);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{infer_table_name, infer_type_name, RenameRule};
+    use proc_macro2::Span;
+    use syn::Ident;
+
+    const RULES: [RenameRule; 5] = [
+        RenameRule::Lower,
+        RenameRule::Camel,
+        RenameRule::Snake,
+        RenameRule::ScreamingSnake,
+        RenameRule::Kebab,
+    ];
+
+    #[test]
+    fn rename_field() {
+        // lowercase, camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case
+        let cases = [
+            ("id", ["id", "id", "id", "ID", "id"]),
+            ("user_id", ["user_id", "userId", "user_id", "USER_ID", "user-id"]),
+            ("created_at_utc", [
+                "created_at_utc",
+                "createdAtUtc",
+                "created_at_utc",
+                "CREATED_AT_UTC",
+                "created-at-utc",
+            ]),
+            ("_private_key", [
+                "_private_key",
+                "privateKey",
+                "_private_key",
+                "_PRIVATE_KEY",
+                "-private-key",
+            ]),
+            ("address_2", ["address_2", "address2", "address_2", "ADDRESS_2", "address-2"]),
+            ("line2_text", ["line2_text", "line2Text", "line2_text", "LINE2_TEXT", "line2-text"]),
+        ];
+
+        for &(field, ref expected) in &cases {
+            for (rule, expected) in RULES.iter().zip(expected) {
+                assert_eq!(rule.apply_to_field(field), *expected, "field `{}`", field);
+            }
+        }
+    }
+
+    #[test]
+    fn rename_variant() {
+        // lowercase, camelCase, snake_case, SCREAMING_SNAKE_CASE, kebab-case
+        let cases = [
+            ("A", ["a", "a", "a", "A", "a"]),
+            ("Active", ["active", "active", "active", "ACTIVE", "active"]),
+            ("OnHold", ["onhold", "onHold", "on_hold", "ON_HOLD", "on-hold"]),
+            ("Level2", ["level2", "level2", "level2", "LEVEL2", "level2"]),
+            ("V2Api", ["v2api", "v2Api", "v2_api", "V2_API", "v2-api"]),
+            ("HTTPLog", ["httplog", "hTTPLog", "h_t_t_p_log", "H_T_T_P_LOG", "h-t-t-p-log"]),
+        ];
+
+        for &(variant, ref expected) in &cases {
+            for (rule, expected) in RULES.iter().zip(expected) {
+                assert_eq!(rule.apply_to_variant(variant), *expected, "variant `{}`", variant);
+            }
+        }
+    }
+
+    #[test]
+    fn table_name() {
+        let cases = [
+            ("User", "users"),
+            ("OrderItem", "order_items"),
+            ("Category", "categories"),
+            ("Day", "days"),
+            ("Key", "keys"),
+            ("Address", "addresses"),
+            ("Box", "boxes"),
+            ("Match", "matches"),
+            ("Wish", "wishes"),
+            ("Status", "statuses"),
+            ("HTTPLog", "http_logs"),
+            ("UserID", "user_ids"),
+            ("Oauth2Token", "oauth2_tokens"),
+        ];
+
+        for &(ident, expected) in &cases {
+            let ident = Ident::new(ident, Span::call_site());
+            assert_eq!(infer_table_name(&ident), expected, "type `{}`", ident);
+        }
+
+        let raw: Ident = syn::parse_str("r#Match").unwrap();
+        assert_eq!(infer_table_name(&raw), "matches");
+    }
+
+    #[test]
+    fn type_name() {
+        let cases = [
+            ("OrderStatus", "order_status"),
+            ("Money", "money"),
+            ("HTTPMethod", "http_method"),
+            ("MoneyUSD", "money_usd"),
+        ];
+
+        for &(ident, expected) in &cases {
+            let ident = Ident::new(ident, Span::call_site());
+            assert_eq!(infer_type_name(&ident), expected, "type `{}`", ident);
+        }
+
+        let raw: Ident = syn::parse_str("r#Match").unwrap();
+        assert_eq!(infer_type_name(&raw), "match");
+    }
+}
@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper = "users"]
+pub struct User {
+    pub id: i64,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct Org {
+    #[pg_mapper]
+    pub id: i64,
+}
+
+fn main() {}
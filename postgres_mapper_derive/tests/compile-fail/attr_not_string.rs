@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = users)]
+pub struct User {
+    #[pg_mapper(rename = 1)]
+    pub id: i64,
+    #[pg_mapper(default = true)]
+    pub name: String,
+}
+
+fn main() {}
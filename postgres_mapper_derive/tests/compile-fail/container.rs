@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(schema = "auth")]
+pub struct User {
+    pub id: i64,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "orgs", tag = "kind", type_name = "org")]
+pub struct Org {
+    pub id: i64,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "events")]
+pub enum Event {
+    Deleted,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "numbers")]
+pub union Number {
+    pub int: i64,
+    pub float: f64,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "accounts", table, schema = "auth")]
+#[pg_mapper(schema = "billing", unquoted, unquoted)]
+pub struct Account {
+    pub id: i64,
+}
+
+fn main() {}
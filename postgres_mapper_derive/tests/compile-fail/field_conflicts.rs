@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct User {
+    #[pg_mapper(rename = "user_id", column = "id")]
+    pub id: i64,
+    #[pg_mapper(skip, rename = "user_name")]
+    pub name: String,
+    #[pg_mapper(flatten, default)]
+    pub org: Org,
+    #[pg_mapper(from_sql = "parse_roles", with = "roles")]
+    pub roles: Vec<String>,
+    #[pg_mapper(skip, try_from = "String")]
+    pub email: String,
+}
+
+pub struct Org {
+    pub id: i64,
+}
+
+fn main() {}
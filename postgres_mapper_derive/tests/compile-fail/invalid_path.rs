@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct User {
+    #[pg_mapper(from_sql = "parse roles")]
+    pub roles: Vec<String>,
+    #[pg_mapper(try_from = "&")]
+    pub id: u64,
+    #[pg_mapper(with = "")]
+    pub tags: Vec<String>,
+    #[pg_mapper(default = "1")]
+    pub n: i64,
+}
+
+fn main() {}
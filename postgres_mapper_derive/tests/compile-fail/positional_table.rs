@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "pairs")]
+pub struct Pair(i64, #[pg_mapper(column = "name")] String, #[pg_mapper(skip)] u8);
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table)]
+pub struct Id(i64);
+
+fn main() {}
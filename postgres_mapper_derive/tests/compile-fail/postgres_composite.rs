@@ -0,0 +1,20 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(Debug, PostgresComposite)]
+pub enum Money {
+    Euro,
+}
+
+#[derive(Debug, PostgresComposite)]
+#[pg_mapper(table = "amounts", schema = "billing", unquoted)]
+pub struct MoneyAmount {
+    pub amount: i64,
+    #[pg_mapper(skip)]
+    pub currency: String,
+}
+
+#[derive(Debug, PostgresComposite)]
+pub struct Point(f64, #[pg_mapper(column = "y")] f64);
+
+fn main() {}
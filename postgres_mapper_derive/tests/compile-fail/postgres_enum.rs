@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(Debug, PostgresEnum)]
+pub struct Status {
+    pub label: String,
+}
+
+#[derive(Debug, PostgresEnum)]
+#[pg_mapper(table = "orders", tag = "status", unquoted)]
+pub enum OrderStatus {
+    Pending,
+    Shipped { tracking: String },
+    Cancelled(String),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users", rename_all = "PascalCase")]
+pub struct User {
+    pub id: i64,
+}
+
+fn main() {}
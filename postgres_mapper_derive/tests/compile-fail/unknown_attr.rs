@@ -0,0 +1,22 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users", schema_name = "public", "users")]
+pub struct User {
+    #[pg_mapper(renamed = "user_id")]
+    pub id: i64,
+    #[pg_mapper("name")]
+    pub name: String,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "events", tag = "kind")]
+pub enum Event {
+    #[pg_mapper(skip)]
+    Deleted,
+    #[pg_mapper("ping")]
+    Ping,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+extern crate trybuild;
+
+#[test]
+fn compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile-fail/*.rs");
+
+    // Attributes requiring features, rejected only while they're disabled.
+    #[cfg(not(feature = "serde"))]
+    cases.compile_fail("tests/missing-features/json.rs");
+    #[cfg(not(all(feature = "postgres-support", feature = "postgres-mapper")))]
+    cases.compile_fail("tests/missing-features/borrowed.rs");
+}
@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct UserName<'a> {
+    pub id: i64,
+    pub name: &'a str,
+    pub avatar: Option<&'a [u8]>,
+}
+
+fn main() {}
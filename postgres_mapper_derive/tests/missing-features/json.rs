@@ -0,0 +1,16 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "prefs")]
+pub struct Prefs {
+    pub id: i64,
+    #[pg_mapper(json)]
+    pub settings: Settings,
+}
+
+pub struct Settings {
+    pub theme: String,
+}
+
+fn main() {}
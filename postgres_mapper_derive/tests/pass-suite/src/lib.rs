@@ -0,0 +1,3 @@
+//! Compiles the cases in `tests/pass` against the `postgres` and
+//! `tokio-postgres` drivers, keeping those out of the dependencies of
+//! `postgres-mapper-derive` itself.
@@ -0,0 +1,7 @@
+extern crate trybuild;
+
+#[test]
+fn pass() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/pass/*.rs");
+}
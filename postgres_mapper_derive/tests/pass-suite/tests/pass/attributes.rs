@@ -0,0 +1,131 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+#[macro_use]
+extern crate serde_derive;
+extern crate postgres;
+extern crate postgres_mapper;
+extern crate tokio_postgres;
+
+use postgres::rows::Row;
+use postgres_mapper::{Error, FromPostgresRow, PostgresFields, PostgresTable};
+use std::convert::TryFrom;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users", rename_all = "camelCase")]
+pub struct User {
+    #[pg_mapper(rename = "user_id")]
+    pub id: i64,
+    pub created_at: String,
+    #[pg_mapper(skip)]
+    pub cached: Vec<u8>,
+    #[pg_mapper(default)]
+    pub nickname: Option<String>,
+    #[pg_mapper(default = "default_locale")]
+    pub locale: String,
+    #[pg_mapper(flatten)]
+    pub audit: Audit,
+    #[pg_mapper(with = "comma_separated")]
+    pub roles: Vec<String>,
+    #[pg_mapper(from_sql = "parse_level")]
+    pub level: u8,
+    #[pg_mapper(try_from = "String")]
+    pub email: Email,
+    #[pg_mapper(try_from = "String")]
+    pub backup_email: Option<Email>,
+    #[pg_mapper(json)]
+    pub settings: Settings,
+    #[pg_mapper(json)]
+    pub extra: Option<Settings>,
+}
+
+#[derive(PostgresMapper)]
+pub struct Audit {
+    pub updated_at: String,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "notes")]
+pub struct Note {
+    pub id: i64,
+    #[pg_mapper(flatten)]
+    pub author: Author,
+}
+
+#[derive(PostgresMapper)]
+pub struct Author {
+    #[pg_mapper(rename = "author,name")]
+    pub name: String,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table, schema = "shop", unquoted)]
+pub struct OrderItem {
+    pub id: i64,
+    pub r#type: String,
+    pub user: i64,
+}
+
+#[derive(Deserialize)]
+pub struct Settings {
+    pub theme: String,
+}
+
+pub struct Email(String);
+
+impl TryFrom<String> for Email {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        match value.contains('@') {
+            true => Ok(Email(value)),
+            false => Err(format!("invalid email {}", value)),
+        }
+    }
+}
+
+mod comma_separated {
+    pub fn from_sql(value: String) -> Result<Vec<String>, String> {
+        Ok(value.split(',').map(str::to_owned).collect())
+    }
+}
+
+fn parse_level(value: i32) -> Result<u8, ::std::num::TryFromIntError> {
+    u8::try_from(value)
+}
+
+fn default_locale() -> String {
+    "en".to_owned()
+}
+
+#[allow(dead_code)]
+fn map(row: &Row) -> Result<(User, OrderItem), Error> {
+    let user = User::from_postgres_row_ref(row)?;
+    let item = OrderItem::from_postgres_row_all(row)?;
+
+    Ok((user, item))
+}
+
+fn main() {
+    assert_eq!(
+        User::sql_fields(),
+        r#" "user_id", "createdAt", "nickname", "locale", "updated_at", "roles", "level", "email", "backupEmail", "settings", "extra" "#,
+    );
+    assert_eq!(User::sql_table(), r#" "users" "#);
+    assert_eq!(User::sql_schema(), None);
+    assert_eq!(
+        Audit::sql_fields_aliased("a", "a_"),
+        r#" a."updated_at" AS "a_updated_at" "#,
+    );
+    assert_eq!(
+        Note::sql_table_dot_fields(),
+        r#" "notes"."id", "notes"."author,name" "#,
+    );
+
+    assert_eq!(OrderItem::sql_table(), " shop.order_items ");
+    assert_eq!(OrderItem::sql_schema(), Some(" shop ".to_owned()));
+    assert_eq!(OrderItem::sql_fields(), r#" id, "type", "user" "#);
+    assert_eq!(
+        OrderItem::sql_table_dot_fields(),
+        r#" shop.order_items.id, shop.order_items."type", shop.order_items."user" "#,
+    );
+}
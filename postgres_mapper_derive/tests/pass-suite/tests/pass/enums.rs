@@ -0,0 +1,86 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+extern crate postgres;
+extern crate postgres_mapper;
+extern crate tokio_postgres;
+
+use postgres::rows::Row;
+use postgres::types::ToSql;
+use postgres_mapper::{Error, FromPostgresRow, PostgresFields, PostgresTable};
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "events", tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    SignedUp {
+        user_id: i64,
+        email: String,
+        #[pg_mapper(rename = "ip,port")]
+        address: String,
+    },
+    #[pg_mapper(rename = "login")]
+    LoggedIn {
+        user_id: i64,
+        #[pg_mapper(rename = "ip,port")]
+        address: String,
+    },
+    Moved(#[pg_mapper(flatten)] Org),
+    Deleted,
+}
+
+#[derive(PostgresMapper)]
+pub struct Org {
+    pub org_id: i64,
+}
+
+#[derive(Debug, PostgresEnum)]
+#[pg_mapper(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Pending,
+    InProgress,
+    #[pg_mapper(rename = "sent")]
+    Shipped,
+}
+
+#[derive(Debug, PostgresComposite)]
+#[pg_mapper(type_name = "money")]
+pub struct MoneyAmount {
+    pub amount: i64,
+    #[pg_mapper(rename = "currency")]
+    pub currency_code: String,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "orders")]
+pub struct Order {
+    pub id: i64,
+    pub status: OrderStatus,
+    pub total: MoneyAmount,
+    pub refunds: Vec<MoneyAmount>,
+}
+
+#[allow(dead_code)]
+fn map(row: &Row) -> Result<(Event, Order), Error> {
+    Ok((Event::from_postgres_row_ref(row)?, Order::from_postgres_row_ref(row)?))
+}
+
+#[allow(dead_code)]
+fn params<'a>(status: &'a OrderStatus, total: &'a MoneyAmount) -> [&'a dyn ToSql; 2] {
+    [status, total]
+}
+
+fn main() {
+    assert_eq!(Event::sql_fields(), r#" "kind", "user_id", "email", "ip,port", "org_id" "#);
+    assert_eq!(
+        Event::sql_table_dot_fields(),
+        r#" "events"."kind", "events"."user_id", "events"."email", "events"."ip,port", "events"."org_id" "#,
+    );
+    assert_eq!(
+        Event::sql_fields_aliased("e", "e_"),
+        r#" e."kind" AS "e_kind", e."user_id" AS "e_user_id", e."email" AS "e_email", e."ip,port" AS "e_ip,port", e."org_id" AS "e_org_id" "#,
+    );
+    assert_eq!(
+        OrderStatus::sql_create_type(),
+        r#"CREATE TYPE "order_status" AS ENUM ('pending', 'in_progress', 'sent')"#,
+    );
+    assert_eq!(Order::sql_fields(), r#" "id", "status", "total", "refunds" "#);
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+extern crate postgres;
+extern crate postgres_mapper;
+extern crate tokio_postgres;
+
+use postgres::rows::Row;
+use postgres_mapper::{Error, FromPostgresRow, FromPostgresRowBorrowed, PostgresFields, PostgresTable};
+use std::marker::PhantomData;
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "pages")]
+pub struct Page<T, U = i64>
+    where T: Clone,
+{
+    pub items: T,
+    #[pg_mapper(flatten)]
+    pub owner: Owner<U>,
+    #[pg_mapper(skip)]
+    pub marker: PhantomData<T>,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "tags")]
+pub struct Tagged<T> {
+    pub id: i64,
+    #[pg_mapper(skip)]
+    pub extra: T,
+}
+
+#[derive(PostgresMapper)]
+pub struct Owner<U> {
+    pub owner_id: U,
+}
+
+#[derive(Debug, PostgresComposite)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct UserRef<'a> {
+    pub id: i64,
+    pub name: &'a str,
+    #[pg_mapper(default)]
+    pub bio: Option<&'a str>,
+    #[pg_mapper(rename = "avatar")]
+    pub data: &'a [u8],
+    #[pg_mapper(flatten)]
+    pub org: OrgRef<'a>,
+}
+
+#[derive(PostgresMapper)]
+pub struct OrgRef<'a> {
+    #[pg_mapper(rename = "org_name")]
+    pub name: &'a str,
+}
+
+#[derive(PostgresMapper)]
+pub struct Wrapper<'a> {
+    #[pg_mapper(flatten)]
+    pub org: OrgRef<'a>,
+}
+
+#[allow(dead_code)]
+fn map<'a>(
+    row: &'a Row,
+) -> Result<(Page<i32>, Page<String, i32>, UserRef<'a>, Tagged<Vec<u8>>, Wrapper<'a>), Error> {
+    Ok((
+        Page::from_postgres_row_ref(row)?,
+        Page::from_postgres_row_all(row)?,
+        UserRef::from_postgres_row_borrowed(row)?,
+        Tagged::from_postgres_row_ref(row)?,
+        Wrapper::from_postgres_row_borrowed(row)?,
+    ))
+}
+
+fn main() {
+    assert_eq!(<Page<i32>>::sql_fields(), r#" "items", "owner_id" "#);
+    assert_eq!(<Tagged<Vec<u8>>>::sql_fields(), r#" "id" "#);
+    assert_eq!(Wrapper::sql_fields(), r#" "org_name" "#);
+    assert_eq!(UserRef::sql_table(), r#" "users" "#);
+    assert_eq!(UserRef::sql_fields(), r#" "id", "name", "bio", "avatar", "org_name" "#);
+    assert_eq!(
+        UserRef::sql_fields_aliased("u", "u_"),
+        r#" u."id" AS "u_id", u."name" AS "u_name", u."bio" AS "u_bio", u."avatar" AS "u_avatar", u."org_name" AS "u_org_name" "#,
+    );
+}
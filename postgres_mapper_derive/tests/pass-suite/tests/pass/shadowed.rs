@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+extern crate postgres;
+extern crate postgres_mapper;
+extern crate tokio_postgres;
+
+#[allow(dead_code)]
+mod shadowed {
+    pub type Result = ();
+    pub struct String;
+    pub struct Option;
+    pub struct Vec;
+    pub struct Box;
+    pub struct Ok;
+    pub struct Err;
+    pub struct Some;
+    pub struct None;
+    pub struct Error;
+    pub struct Debug;
+
+    #[derive(PostgresMapper)]
+    #[pg_mapper(table = "users", tag = "kind")]
+    pub enum Account {
+        Person { id: i64, name: ::std::string::String },
+        Bot { id: i64 },
+    }
+
+    #[derive(PostgresMapper)]
+    #[pg_mapper(table = "users")]
+    pub struct User {
+        pub id: i64,
+        pub name: ::std::option::Option<::std::string::String>,
+        #[pg_mapper(flatten)]
+        pub org: Org,
+        pub status: Status,
+        pub point: Point,
+    }
+
+    #[derive(PostgresMapper)]
+    pub struct Org {
+        pub org_id: i64,
+    }
+
+    #[derive(PostgresMapper)]
+    pub struct Pair(i64, i64);
+
+    #[derive(::std::fmt::Debug, PostgresEnum)]
+    pub enum Status {
+        Active,
+        Banned,
+    }
+
+    #[derive(::std::fmt::Debug, PostgresComposite)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+}
+
+fn main() {
+    use postgres_mapper::PostgresFields;
+
+    assert_eq!(shadowed::User::sql_fields(), r#" "id", "name", "org_id", "status", "point" "#);
+    assert_eq!(shadowed::Account::sql_fields(), r#" "kind", "id", "name" "#);
+}
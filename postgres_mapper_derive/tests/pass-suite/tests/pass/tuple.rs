@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate postgres_mapper_derive;
+extern crate postgres;
+extern crate postgres_mapper;
+extern crate tokio_postgres;
+
+use postgres::rows::Row;
+use postgres_mapper::{Error, FromPostgresRow, FromPostgresRowTuple, PostgresFields, PostgresTable};
+
+#[derive(PostgresMapper)]
+pub struct Pair(i64, String);
+
+#[derive(PostgresMapper)]
+pub struct Id(i64);
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "users")]
+pub struct UserName(
+    #[pg_mapper(column = "id")] i64,
+    #[pg_mapper(skip)] u8,
+    #[pg_mapper(column = "name")] String,
+);
+
+#[derive(PostgresMapper)]
+#[pg_mapper(table = "orgs")]
+pub struct Org {
+    pub id: i64,
+    pub name: String,
+}
+
+#[allow(dead_code)]
+fn map(row: &Row) -> Result<(Pair, Id, UserName, Org), Error> {
+    let pair = Pair::from_postgres_row_ref(row)?;
+    let id = Id::from_postgres_row_prefixed(row, "")?;
+    let (user, org) = <(UserName, Org)>::from_postgres_row_tuple(row, ("u_", "o_"))?;
+
+    Ok((pair, id, user, org))
+}
+
+fn main() {
+    assert_eq!(UserName::sql_table(), r#" "users" "#);
+    assert_eq!(UserName::sql_fields(), r#" "id", "name" "#);
+    assert_eq!(
+        Org::sql_fields_aliased("o", "o_"),
+        r#" o."id" AS "o_id", o."name" AS "o_name" "#,
+    );
+}
@@ -45,7 +45,7 @@
 //! #[macro_use] extern crate postgres_mapper_derive;
 //! extern crate postgres_mapper;
 //!
-//! use postgres_mapper::FromPostgresRow;
+//! use postgres_mapper::{FromPostgresRow, PostgresFields, PostgresTable};
 //!
 //! #[derive(PostgresMapper)]
 //! pub struct User {
@@ -60,6 +60,265 @@
 //! let user = User::from_postgres_row(row)?;
 //! ```
 //!
+//! `sql_fields()` and `sql_fields_aliased()` belong to the `PostgresFields` trait,
+//! implemented for every mapped type whose columns all have names.
+//!
+//! ### Attributes
+//!
+//! The struct and its fields can be customized with the `pg_mapper` attribute.
+//!
+//! Struct attributes:
+//!
+//! - `#[pg_mapper(table = "...")]`: the table returned by `sql_table()` and used
+//! by `sql_table_dot_fields()`. These methods belong to the `PostgresTable` trait,
+//! which is only implemented for types declaring a table, so structs mapping
+//! projections such as report rows can leave it out. A bare `#[pg_mapper(table)]`
+//! infers the snake_case plural of the type's name, such as `order_items` for
+//! `OrderItem`.
+//! - `#[pg_mapper(schema = "...")]`: the schema qualifying the table in
+//! `sql_table()` and `sql_table_dot_fields()`, also returned by `sql_schema()`.
+//! - `#[pg_mapper(rename_all = "...")]`: apply a casing rule to the column name of
+//! every field that isn't explicitly renamed. One of `lowercase`, `camelCase`,
+//! `snake_case`, `SCREAMING_SNAKE_CASE` or `kebab-case`.
+//! - `#[pg_mapper(tag = "...")]`: the column choosing the variant of an enum, see
+//! [Mapping enums by tag](#mapping-enums-by-tag).
+//! - `#[pg_mapper(unquoted)]`: leave table and column names unquoted in
+//! `sql_table()`, `sql_fields()`, `sql_table_dot_fields()` and
+//! `sql_fields_aliased()`, except for reserved words.
+//!
+//! Field attributes:
+//!
+//! - `#[pg_mapper(rename = "...")]`: map the field from a column with a different
+//! name than the field's identifier.
+//! - `#[pg_mapper(skip)]`: never read the field from a row, filling it with
+//! `Default::default()` instead. Skipped fields are left out of `sql_fields()` and
+//! `sql_table_dot_fields()`.
+//! - `#[pg_mapper(default)]`: fall back to `Default::default()` when the field's
+//! column is missing from the row, instead of returning
+//! `Error::ColumnNotFound`.
+//! - `#[pg_mapper(default = "...")]`: like `default`, but calls the given
+//! function to create the value. When combined with `skip`, the function is used
+//! for the skipped field.
+//! - `#[pg_mapper(flatten)]`: map the field's type, which must also derive
+//! `PostgresMapper`, from the same row. Its columns are expanded in place in
+//! `sql_fields()` and `sql_table_dot_fields()`.
+//! - `#[pg_mapper(from_sql = "...")]`: read the column as the argument type of the
+//! given function, then convert it into the field's type by calling the function.
+//! The function returns a `Result` whose error converts into
+//! `Box<dyn Error + Send + Sync>`; failures are returned as `Error::Custom`.
+//! - `#[pg_mapper(with = "...")]`: like `from_sql`, using the `from_sql` function
+//! of the given module.
+//! - `#[pg_mapper(try_from = "...")]`: read the column as the given type, then
+//! convert it into the field's type through `TryFrom`. The `TryFrom` error must
+//! convert into `Box<dyn Error + Send + Sync>`; failures are returned as
+//! `Error::TryFrom`. For `Option<T>` fields the column is read as an `Option` of
+//! the given type and only non-`NULL` values are converted into `T`.
+//! - `#[pg_mapper(json)]`: read a `json` or `jsonb` column and deserialize it into
+//! the field's type with `serde`. Failures are returned as `Error::Json`, naming
+//! the column. `NULL` columns are deserialized from JSON's `null`, so `Option`
+//! fields map them to `None`. Requires the `serde` feature of both crates.
+//! - `#[pg_mapper(column = "...")]`: the same as `rename`. On a tuple struct
+//! field, reads the field from the named column instead of by position.
+//!
+//! Renamed columns are also used by `sql_fields()` and `sql_table_dot_fields()`.
+//! Fields named with raw identifiers, such as `r#type`, map from the column
+//! without the `r#` prefix.
+//!
+//! Table and column names are double quoted in the generated SQL fragments,
+//! keeping their case, and the columns selected by `sql_fields_aliased()` are
+//! named by quoted aliases. With `unquoted`, only columns named after raw
+//! identifiers and names that are Postgres reserved words, such as `user` or
+//! `order`, are quoted.
+//!
+//! ```rust
+//! #[derive(PostgresMapper)]
+//! #[pg_mapper(table = "user", rename_all = "camelCase")]
+//! pub struct User {
+//!     #[pg_mapper(rename = "user_id")]
+//!     pub id: i64,
+//!     // mapped from the `createdAt` column
+//!     pub created_at: String,
+//!     // a `text` column holding a comma separated list
+//!     #[pg_mapper(with = "comma_separated")]
+//!     pub roles: Vec<String>,
+//! }
+//!
+//! mod comma_separated {
+//!     pub fn from_sql(value: String) -> Result<Vec<String>, String> {
+//!         Ok(value.split(',').map(str::to_owned).collect())
+//!     }
+//! }
+//! ```
+//!
+//! Tuple structs are mapped by position: each field is read from the column at
+//! its index, and a newtype struct from the row's first column. As positional
+//! fields have no name to prefix, the `*_prefixed` methods return
+//! `Error::PositionalPrefix` when given a prefix. Tuple structs only implement
+//! `PostgresFields`, and may only declare a `table`, when every field declares a
+//! `column`:
+//!
+//! ```rust
+//! #[derive(PostgresMapper)]
+//! #[pg_mapper(table = "user")]
+//! pub struct UserName(#[pg_mapper(column = "id")] i64, #[pg_mapper(column = "name")] String);
+//! ```
+//!
+//! Generic types keep their parameters and where clauses in the derived
+//! implementations, which also require the type of each field using a type
+//! parameter to implement the trait reading it, such as `FromSql`. Fields
+//! converted with `with` or `from_sql` are not bounded; declare their bounds on
+//! the type itself.
+//!
+//! ### Mapping joins
+//!
+//! When several joined tables share column names, select each table's columns with
+//! a prefix and map them back with the same prefix:
+//!
+//! ```rust
+//! let query = format!(
+//!     "SELECT {}, {} FROM users u JOIN orgs o ON o.id = u.org_id",
+//!     User::sql_fields_aliased("u", "u_"),
+//!     Org::sql_fields_aliased("o", "o_"),
+//! );
+//!
+//! for row in &conn.query(&query, &[])? {
+//!     let user = User::from_postgres_row_prefixed(&row, "u_")?;
+//!     let org = Org::from_postgres_row_prefixed(&row, "o_")?;
+//! }
+//! ```
+//!
+//! Tuples of up to 8 mapped types can also be read from one row with the
+//! `FromPostgresRowTuple` and `FromTokioPostgresRowTuple` traits:
+//!
+//! ```rust
+//! use postgres_mapper::FromPostgresRowTuple;
+//!
+//! let (user, org) = <(User, Org)>::from_postgres_row_tuple(&row, ("u_", "o_"))?;
+//! ```
+//!
+//! ### Reporting every error
+//!
+//! The mappers return the first error they encounter. While tracking down a
+//! mismatch between a query and a struct, `from_postgres_row_all` and
+//! `from_tokio_postgres_row_all` instead read every field, including those of
+//! flattened types, and return all missing columns and type mismatches at once
+//! as `Error::Multiple`:
+//!
+//! ```rust
+//! match User::from_postgres_row_all(&row) {
+//!     Ok(user) => println!("{}", user.name),
+//!     // "2 errors mapping row: Column `id` of type int4 can not be read as
+//!     // `i64`, mapping field `id` of `User`; Column `name` in row not found,
+//!     // mapping field `name` of `User`"
+//!     Err(error) => eprintln!("{}", error),
+//! }
+//! ```
+//!
+//! `from_postgres_row_all_prefixed` and `from_tokio_postgres_row_all_prefixed`
+//! read prefixed columns in the same way.
+//!
+//! ### Borrowing from rows
+//!
+//! With the `postgres-support` features, mapped types also implement
+//! `FromPostgresRowBorrowed<'a>`, mapping from a borrowed `postgres` `Row`. Fields
+//! whose type uses a lifetime parameter of the type, such as `&'a str`,
+//! `&'a [u8]` or `Option`s of them, are then borrowed from the row instead of
+//! being copied:
+//!
+//! ```rust
+//! use postgres_mapper::FromPostgresRowBorrowed;
+//!
+//! #[derive(PostgresMapper)]
+//! #[pg_mapper(table = "user")]
+//! pub struct UserName<'a> {
+//!     pub id: i64,
+//!     pub name: &'a str,
+//! }
+//!
+//! for row in &conn.query("SELECT id, name FROM user", &[])? {
+//!     let user = UserName::from_postgres_row_borrowed(&row)?;
+//! }
+//! ```
+//!
+//! Types with borrowed fields only implement `FromPostgresRowBorrowed`, along with
+//! `PostgresFields` and, when declaring a table, `PostgresTable` for building
+//! their queries.
+//!
+//! ### Mapping enums by tag
+//!
+//! An enum holding several kinds of rows of one table can derive `PostgresMapper`
+//! by naming the column holding each row's tag with `#[pg_mapper(tag = "...")]`.
+//! The variant whose tag matches the column's value is mapped from the row, with
+//! its fields read like those of a struct. Variants are tagged with their name,
+//! which can be changed with `#[pg_mapper(rename = "...")]` on a variant or
+//! `#[pg_mapper(rename_all = "...")]` on the enum.
+//!
+//! Tags matching no variant are returned as `Error::UnknownTag`. The SQL helpers
+//! list the tag column followed by the columns of every variant.
+//!
+//! ```rust
+//! #[derive(PostgresMapper)]
+//! #[pg_mapper(table = "events", tag = "kind", rename_all = "snake_case")]
+//! pub enum Event {
+//!     // mapped from rows whose `kind` is `signed_up`
+//!     SignedUp { user_id: i64, email: String },
+//!     #[pg_mapper(rename = "login")]
+//!     LoggedIn { user_id: i64 },
+//!     Deleted,
+//! }
+//! ```
+//!
+//! ### Postgres ENUM types
+//!
+//! The `PostgresEnum` proc-macro implements `FromSql` and `ToSql` for an enum of
+//! unit variants backed by a Postgres ENUM type, so it can be used as a field of a
+//! mapped struct or as a query parameter. The enum must also implement `Debug`.
+//!
+//! Each variant is mapped to the label of the same name. Variants can be renamed
+//! with `#[pg_mapper(rename = "...")]`, and every label cased with
+//! `#[pg_mapper(rename_all = "...")]` on the enum. The type's name defaults to the
+//! enum's name in snake_case and can be set with
+//! `#[pg_mapper(type_name = "...")]`, which is quoted in `sql_create_type()` so
+//! that the created type keeps its case.
+//!
+//! `sql_create_type()` returns the matching `CREATE TYPE` statement:
+//!
+//! ```rust
+//! #[derive(Debug, PostgresEnum)]
+//! #[pg_mapper(rename_all = "snake_case")]
+//! pub enum OrderStatus {
+//!     Pending,
+//!     InProgress,
+//!     #[pg_mapper(rename = "sent")]
+//!     Shipped,
+//! }
+//!
+//! // CREATE TYPE "order_status" AS ENUM ('pending', 'in_progress', 'sent')
+//! let statement = OrderStatus::sql_create_type();
+//! ```
+//!
+//! ### Postgres composite types
+//!
+//! The `PostgresComposite` proc-macro implements `FromSql` and `ToSql` for a struct
+//! backed by a Postgres composite type, so it can be read from and written to
+//! columns of that type and of arrays of it, such as a `Vec<MoneyAmount>` field.
+//! The struct must also implement `Debug`.
+//!
+//! Each field is mapped to the attribute of the same name, which can be changed
+//! with `#[pg_mapper(rename = "...")]` or `#[pg_mapper(rename_all = "...")]`. The
+//! type's name defaults to the struct's name in snake_case and can be set with
+//! `#[pg_mapper(type_name = "...")]`.
+//!
+//! ```rust
+//! // CREATE TYPE money_amount AS (amount numeric, currency text)
+//! #[derive(Debug, PostgresComposite)]
+//! pub struct MoneyAmount {
+//!     pub amount: Decimal,
+//!     #[pg_mapper(rename = "currency")]
+//!     pub currency_code: String,
+//! }
+//! ```
+//!
 //! ### The two crates
 //!
 //! This repository contains two crates: `postgres-mapper` which contains an `Error`
@@ -82,6 +341,9 @@
 //! `tokio-postgres-support`. When one is enabled in `postgres-mapper-derive`, it
 //! must also be enabled in `postgres-mapper`.
 //!
+//! Both crates also have a `serde` feature, enabling `json` fields deserialized
+//! through `serde_json`.
+//!
 //! ### Installation
 //!
 //! The above might be confusing, so here's an example where `tokio-postgres` is
@@ -107,62 +369,85 @@
 extern crate postgres;
 #[cfg(feature = "tokio-postgres-support")]
 extern crate tokio_postgres;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
 use postgres::Error as PostgresError;
+use std::any::type_name;
+use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
 #[cfg(feature = "postgres-support")]
-use postgres::rows::Row as PostgresRow;
+use postgres::rows::{Row as PostgresRow, RowIndex};
+#[cfg(feature = "postgres-support")]
+use postgres::types::{FromSql as PostgresFromSql, Type as PostgresType, WasNull};
+#[cfg(feature = "tokio-postgres-support")]
+use tokio_postgres::rows::{Row as TokioRow, RowIndex as TokioRowIndex};
 #[cfg(feature = "tokio-postgres-support")]
-use tokio_postgres::rows::Row as TokioRow;
+use tokio_postgres::types::FromSql as TokioFromSql;
+#[cfg(all(feature = "tokio-postgres-support", not(feature = "postgres-support")))]
+use tokio_postgres::types::Type as PostgresType;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde_json::Error as JsonError;
 
-/// Trait containing various methods for converting from a postgres Row to a
-/// mapped type.
+/// Trait containing the methods of a mapped type that depend on its table.
 ///
 /// When using the `postgres_mapper_derive` crate's `PostgresMapper` proc-macro,
-/// this will automatically be implemented on types.
-///
-/// The [`from_postgres_row`] method exists for consuming a `Row` - useful for
-/// iterator mapping - while [`from_postgres_row_ref`] exists for borrowing a
-/// `Row`.
-#[cfg(feature = "postgres-support")]
-pub trait FromPostgresRow: Sized {
-    /// Converts from a postgres `Row` into a mapped type, consuming the given
-    /// `Row`.
-    ///
-    /// # Errors
+/// this will automatically be implemented on types annotated with a
+/// `#[pg_mapper(table = "...")]` attribute, along with `FromPostgresRow` or
+/// `FromTokioPostgresRow`. Types without a table, such as the rows of a report,
+/// don't implement it.
+pub trait PostgresTable {
+    /// Get the name of the annotated sql table name.
     ///
-    /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
-    /// found.
+    /// Example:
     ///
-    /// Returns [`Error::Postgres`] if there was an error converting the row
-    /// column to the requested type.
+    /// The following will return the String ` "user" `.
+    /// Note the extra spaces on either side to avoid incorrect formatting.
     ///
-    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
-    /// [`Error::Postgres`]: enum.Error.html#variant.Postgres
-    fn from_postgres_row(row: PostgresRow) -> Result<Self, Error>;
+    /// ```
+    ///     #[derive(PostgresMapper)]
+    ///     #[pg_mapper(table = "user")]
+    ///     pub struct User {
+    ///         pub id: i64,
+    ///         pub email: Option<String>,
+    ///     }
+    /// ```
+    fn sql_table() -> String;
 
-    /// Converts from a `postgres` `Row` into a mapped type, borrowing the given
-    /// `Row`.
-    ///
-    /// # Errors
+    /// Get the name of the annotated sql schema, if the table is
+    /// schema-qualified.
     ///
-    /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
-    /// found.
+    /// Example:
     ///
-    /// Returns [`Error::Postgres`] if there was an error converting the row
-    /// column to the requested type.
+    /// The following will return `Some` of the String ` "billing" `, while
+    /// `sql_table()` returns ` "billing"."invoices" `.
     ///
-    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
-    /// [`Error::Postgres`]: enum.Error.html#variant.Postgres
-    fn from_postgres_row_ref(row: &PostgresRow) -> Result<Self, Error>;
+    /// ```
+    ///     #[derive(PostgresMapper)]
+    ///     #[pg_mapper(schema = "billing", table = "invoices")]
+    ///     pub struct Invoice {
+    ///         pub id: i64,
+    ///         pub total: i64,
+    ///     }
+    /// ```
+    fn sql_schema() -> Option<String>;
 
-    /// Get the name of the annotated sql table name.
+    /// Get a list of the field names which can be used to construct
+    /// a SQL query.
+    ///
+    /// Each field is scoped to the table, so that joined tables sharing column
+    /// names can be selected.
     ///
     /// Example:
     ///
-    /// The following will return the String " user ".
+    /// The following will return the String ` "user"."id", "user"."email" `.
     /// Note the extra spaces on either side to avoid incorrect formatting.
     ///
     /// ```
@@ -173,17 +458,24 @@ pub trait FromPostgresRow: Sized {
     ///         pub email: Option<String>,
     ///     }
     /// ```
-    fn sql_table() -> String;
+    ///
+    fn sql_table_dot_fields() -> String;
+}
 
+/// Trait containing the methods listing the columns a mapped type is read
+/// from.
+///
+/// When using the `postgres_mapper_derive` crate's `PostgresMapper` proc-macro,
+/// this will automatically be implemented on types whose fields are all read
+/// from named columns. Tuple structs reading fields by position don't
+/// implement it unless every field declares a `#[pg_mapper(column = "...")]`.
+pub trait PostgresFields {
     /// Get a list of the field names which can be used to construct
     /// a SQL query.
     ///
-    /// We also expect an attribute tag #[pg_mapper(table = "foo")]
-    /// so that a scoped list of fields can be generated.
-    ///
     /// Example:
     ///
-    /// The following will return the String " id, email ".
+    /// The following will return the String ` "id", "email" `.
     /// Note the extra spaces on either side to avoid incorrect formatting.
     ///
     /// ```
@@ -197,15 +489,16 @@ pub trait FromPostgresRow: Sized {
     ///
     fn sql_fields() -> String;
 
-    /// Get a list of the field names which can be used to construct
-    /// a SQL query.
+    /// Get a list of the field names qualified with a table alias, each
+    /// selected as the column name with a prefix prepended.
     ///
-    /// We also expect an attribute tag #[pg_mapper(table = "foo")]
-    /// so that a scoped list of fields can be generated.
+    /// The result can be mapped back with `from_postgres_row_prefixed` or
+    /// `from_tokio_postgres_row_prefixed` using the same prefix.
     ///
     /// Example:
     ///
-    /// The following will return the String " user.id, user.email ".
+    /// Calling `User::sql_fields_aliased("u", "u_")` on the following will
+    /// return the String ` u."id" AS "u_id", u."email" AS "u_email" `.
     /// Note the extra spaces on either side to avoid incorrect formatting.
     ///
     /// ```
@@ -216,8 +509,108 @@ pub trait FromPostgresRow: Sized {
     ///         pub email: Option<String>,
     ///     }
     /// ```
+    fn sql_fields_aliased(alias: &str, prefix: &str) -> String;
+
+    /// Get the list of quoted column names joined by [`sql_fields`].
     ///
-    fn sql_table_dot_fields() -> String;
+    /// Used by implementations derived with `postgres_mapper_derive` to list
+    /// the columns of flattened fields.
+    ///
+    /// [`sql_fields`]: #tymethod.sql_fields
+    #[doc(hidden)]
+    fn sql_columns() -> Vec<String>;
+
+    /// Get the list of aliased columns joined by [`sql_fields_aliased`].
+    ///
+    /// [`sql_fields_aliased`]: #tymethod.sql_fields_aliased
+    #[doc(hidden)]
+    fn sql_columns_aliased(alias: &str, prefix: &str) -> Vec<String>;
+}
+
+/// Trait containing various methods for converting from a postgres Row to a
+/// mapped type.
+///
+/// When using the `postgres_mapper_derive` crate's `PostgresMapper` proc-macro,
+/// this will automatically be implemented on types.
+///
+/// The [`from_postgres_row`] method exists for consuming a `Row` - useful for
+/// iterator mapping - while [`from_postgres_row_ref`] exists for borrowing a
+/// `Row`.
+#[cfg(feature = "postgres-support")]
+pub trait FromPostgresRow: Sized {
+    /// Converts from a postgres `Row` into a mapped type, consuming the given
+    /// `Row`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
+    /// found, or another [`Error`] if a column could not be read into its
+    /// field.
+    ///
+    /// [`Error`]: enum.Error.html
+    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
+    fn from_postgres_row(row: PostgresRow) -> Result<Self, Error>;
+
+    /// Converts from a `postgres` `Row` into a mapped type, borrowing the given
+    /// `Row`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
+    /// found, or another [`Error`] if a column could not be read into its
+    /// field.
+    ///
+    /// [`Error`]: enum.Error.html
+    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
+    fn from_postgres_row_ref(row: &PostgresRow) -> Result<Self, Error>;
+
+    /// Converts from a `postgres` `Row` into a mapped type, reading every
+    /// column with the given prefix prepended to its name.
+    ///
+    /// This is useful for mapping a `JOIN` of several tables sharing column
+    /// names, selected with [`sql_fields_aliased`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ColumnNotFound`] if the prefixed column in a mapping
+    /// was not found, or another [`Error`] if a column could not be read into
+    /// its field.
+    ///
+    /// [`sql_fields_aliased`]: trait.PostgresFields.html#tymethod.sql_fields_aliased
+    /// [`Error`]: enum.Error.html
+    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
+    fn from_postgres_row_prefixed(row: &PostgresRow, prefix: &str) -> Result<Self, Error>;
+
+    /// Converts from a `postgres` `Row` into a mapped type like
+    /// [`from_postgres_row_ref`], but reads every field before failing.
+    ///
+    /// This is useful for finding every mismatch between a type and its
+    /// table at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Multiple`] holding the error of every field that
+    /// could not be mapped, such as missing columns and columns of the wrong
+    /// type. Errors of flattened fields are included in the same list.
+    ///
+    /// [`from_postgres_row_ref`]: #tymethod.from_postgres_row_ref
+    /// [`Error::Multiple`]: enum.Error.html#variant.Multiple
+    fn from_postgres_row_all(row: &PostgresRow) -> Result<Self, Error>;
+
+    /// Converts from a `postgres` `Row` into a mapped type like
+    /// [`from_postgres_row_all`], reading every column with the given prefix
+    /// prepended to its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Multiple`] holding the error of every field that
+    /// could not be mapped, including an [`Error::PositionalPrefix`] if the
+    /// type reads some of its fields by position.
+    ///
+    /// [`from_postgres_row_all`]: #tymethod.from_postgres_row_all
+    /// [`Error::Multiple`]: enum.Error.html#variant.Multiple
+    /// [`Error::PositionalPrefix`]: enum.Error.html#variant.PositionalPrefix
+    fn from_postgres_row_all_prefixed(row: &PostgresRow, prefix: &str) -> Result<Self, Error>;
 }
 
 /// Trait containing various methods for converting from a `tokio-postgres` Row
@@ -237,13 +630,11 @@ pub trait FromTokioPostgresRow: Sized {
     /// # Errors
     ///
     /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
-    /// found.
-    ///
-    /// Returns [`Error::Conversion`] if there was an error converting the row
-    /// column to the requested type.
+    /// found, or another [`Error`] if a column could not be read into its
+    /// field.
     ///
+    /// [`Error`]: enum.Error.html
     /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
-    /// [`Error::Conversion`]: enum.Error.html#variant.Conversion
     fn from_tokio_postgres_row(row: TokioRow) -> Result<Self, Error>;
 
     /// Converts from a `tokio-postgres` `Row` into a mapped type, borrowing the
@@ -252,93 +643,600 @@ pub trait FromTokioPostgresRow: Sized {
     /// # Errors
     ///
     /// Returns [`Error::ColumnNotFound`] if the column in a mapping was not
-    /// found.
-    ///
-    /// Returns [`Error::Conversion`] if there was an error converting the row
-    /// column into the requested type.
+    /// found, or another [`Error`] if a column could not be read into its
+    /// field.
     ///
+    /// [`Error`]: enum.Error.html
     /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
-    /// [`Error::Conversion`]: enum.Error.html#variant.Conversion
     fn from_tokio_postgres_row_ref(row: &TokioRow) -> Result<Self, Error>;
 
-    /// Get the name of the annotated sql table name.
+    /// Converts from a `tokio-postgres` `Row` into a mapped type, reading
+    /// every column with the given prefix prepended to its name.
     ///
-    /// Example:
+    /// This is useful for mapping a `JOIN` of several tables sharing column
+    /// names, selected with [`sql_fields_aliased`].
     ///
-    /// The following will return the String " user ".
-    /// Note the extra spaces on either side to avoid incorrect formatting.
+    /// # Errors
     ///
-    /// ```
-    ///     #[derive(PostgresMapper)]
-    ///     #[pg_mapper(table = "user")]
-    ///     pub struct User {
-    ///         pub id: i64,
-    ///         pub email: Option<String>,
-    ///     }
-    /// ```
-    fn sql_table() -> String;
+    /// Returns [`Error::ColumnNotFound`] if the prefixed column in a mapping
+    /// was not found, or another [`Error`] if a column could not be read into
+    /// its field.
+    ///
+    /// [`sql_fields_aliased`]: trait.PostgresFields.html#tymethod.sql_fields_aliased
+    /// [`Error`]: enum.Error.html
+    /// [`Error::ColumnNotFound`]: enum.Error.html#variant.ColumnNotFound
+    fn from_tokio_postgres_row_prefixed(row: &TokioRow, prefix: &str) -> Result<Self, Error>;
 
-    /// Get a list of the field names which can be used to construct
-    /// a SQL query.
+    /// Converts from a `tokio-postgres` `Row` into a mapped type like
+    /// [`from_tokio_postgres_row_ref`], but reads every field before failing.
     ///
-    /// We also expect an attribute tag #[pg_mapper(table = "foo")]
-    /// so that a scoped list of fields can be generated.
+    /// This is useful for finding every mismatch between a type and its
+    /// table at once.
     ///
-    /// Example:
+    /// # Errors
     ///
-    /// The following will return the String " id, email ".
-    /// Note the extra spaces on either side to avoid incorrect formatting.
+    /// Returns [`Error::Multiple`] holding the error of every field that
+    /// could not be mapped, such as missing columns and columns of the wrong
+    /// type. Errors of flattened fields are included in the same list.
     ///
-    /// ```
-    ///     #[derive(PostgresMapper)]
-    ///     #[pg_mapper(table = "user")]
-    ///     pub struct User {
-    ///         pub id: i64,
-    ///         pub email: Option<String>,
-    ///     }
-    /// ```
+    /// [`from_tokio_postgres_row_ref`]: #tymethod.from_tokio_postgres_row_ref
+    /// [`Error::Multiple`]: enum.Error.html#variant.Multiple
+    fn from_tokio_postgres_row_all(row: &TokioRow) -> Result<Self, Error>;
+
+    /// Converts from a `tokio-postgres` `Row` into a mapped type like
+    /// [`from_tokio_postgres_row_all`], reading every column with the given
+    /// prefix prepended to its name.
     ///
-    fn sql_fields() -> String;
+    /// # Errors
+    ///
+    /// Returns [`Error::Multiple`] holding the error of every field that
+    /// could not be mapped, including an [`Error::PositionalPrefix`] if the
+    /// type reads some of its fields by position.
+    ///
+    /// [`from_tokio_postgres_row_all`]: #tymethod.from_tokio_postgres_row_all
+    /// [`Error::Multiple`]: enum.Error.html#variant.Multiple
+    /// [`Error::PositionalPrefix`]: enum.Error.html#variant.PositionalPrefix
+    fn from_tokio_postgres_row_all_prefixed(row: &TokioRow, prefix: &str) -> Result<Self, Error>;
+}
 
-    /// Get a list of the field names which can be used to construct
-    /// a SQL query.
+/// Trait for converting from a single postgres Row into a tuple of mapped
+/// types, each read with its own column prefix.
+///
+/// This is implemented for tuples of up to 8 types implementing
+/// [`FromPostgresRow`], so that a `JOIN` selected with each type's
+/// [`sql_fields_aliased`] can be mapped in one go:
+///
+/// ```rust
+/// let (user, org) = <(User, Org)>::from_postgres_row_tuple(&row, ("u_", "o_"))?;
+/// ```
+///
+/// [`FromPostgresRow`]: trait.FromPostgresRow.html
+/// [`sql_fields_aliased`]: trait.PostgresFields.html#tymethod.sql_fields_aliased
+#[cfg(feature = "postgres-support")]
+pub trait FromPostgresRowTuple<'a>: Sized {
+    /// A tuple of column prefixes, one for each mapped type.
+    type Prefixes;
+
+    /// Converts from a `postgres` `Row` into a tuple of mapped types, reading
+    /// each with the prefix at the same position in `prefixes`.
     ///
-    /// We also expect an attribute tag #[pg_mapper(table = "foo")]
-    /// so that a scoped list of fields can be generated.
+    /// # Errors
     ///
-    /// Example:
+    /// Returns the first error encountered while mapping any of the types.
+    /// Refer to [`FromPostgresRow::from_postgres_row_prefixed`].
     ///
-    /// The following will return the String " user.id, user.email ".
-    /// Note the extra spaces on either side to avoid incorrect formatting.
+    /// [`FromPostgresRow::from_postgres_row_prefixed`]: trait.FromPostgresRow.html#tymethod.from_postgres_row_prefixed
+    fn from_postgres_row_tuple(row: &PostgresRow, prefixes: Self::Prefixes) -> Result<Self, Error>;
+}
+
+/// Trait for converting from a single `tokio-postgres` Row into a tuple of
+/// mapped types, each read with its own column prefix.
+///
+/// This is implemented for tuples of up to 8 types implementing
+/// [`FromTokioPostgresRow`], so that a `JOIN` selected with each type's
+/// [`sql_fields_aliased`] can be mapped in one go:
+///
+/// ```rust
+/// let (user, org) = <(User, Org)>::from_tokio_postgres_row_tuple(&row, ("u_", "o_"))?;
+/// ```
+///
+/// [`FromTokioPostgresRow`]: trait.FromTokioPostgresRow.html
+/// [`sql_fields_aliased`]: trait.PostgresFields.html#tymethod.sql_fields_aliased
+#[cfg(feature = "tokio-postgres-support")]
+pub trait FromTokioPostgresRowTuple<'a>: Sized {
+    /// A tuple of column prefixes, one for each mapped type.
+    type Prefixes;
+
+    /// Converts from a `tokio-postgres` `Row` into a tuple of mapped types,
+    /// reading each with the prefix at the same position in `prefixes`.
     ///
-    /// ```
-    ///     #[derive(PostgresMapper)]
-    ///     #[pg_mapper(table = "user")]
-    ///     pub struct User {
-    ///         pub id: i64,
-    ///         pub email: Option<String>,
-    ///     }
-    /// ```
+    /// # Errors
     ///
-    fn sql_table_dot_fields() -> String;
+    /// Returns the first error encountered while mapping any of the types.
+    /// Refer to [`FromTokioPostgresRow::from_tokio_postgres_row_prefixed`].
+    ///
+    /// [`FromTokioPostgresRow::from_tokio_postgres_row_prefixed`]: trait.FromTokioPostgresRow.html#tymethod.from_tokio_postgres_row_prefixed
+    fn from_tokio_postgres_row_tuple(row: &TokioRow, prefixes: Self::Prefixes) -> Result<Self, Error>;
+}
+
+/// Trait for converting from a borrowed `postgres` Row into a mapped type
+/// which may borrow from it, such as a struct holding `&'a str` and `&'a [u8]`
+/// fields, to avoid allocating a `String` or `Vec<u8>` per column.
+///
+/// When using the `postgres_mapper_derive` crate's `PostgresMapper` proc-macro,
+/// this will automatically be implemented on types. Fields whose type uses a
+/// lifetime parameter of the mapped type are read through [`FromSqlBorrowed`],
+/// while other fields are read as usual:
+///
+/// ```rust
+/// #[derive(PostgresMapper)]
+/// #[pg_mapper(table = "user")]
+/// pub struct UserName<'a> {
+///     pub id: i64,
+///     pub name: &'a str,
+/// }
+///
+/// for row in &conn.query("SELECT id, name FROM user", &[])? {
+///     let user = UserName::from_postgres_row_borrowed(&row)?;
+/// }
+/// ```
+///
+/// [`FromSqlBorrowed`]: trait.FromSqlBorrowed.html
+#[cfg(feature = "postgres-support")]
+pub trait FromPostgresRowBorrowed<'a>: Sized {
+    /// Converts from a `postgres` `Row` into a mapped type, borrowing from the
+    /// given `Row`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FromPostgresRow::from_postgres_row_ref`].
+    ///
+    /// Returns [`Error::Decode`] if a borrowed field could not be read from
+    /// its column.
+    ///
+    /// [`Error::Decode`]: enum.Error.html#variant.Decode
+    /// [`FromPostgresRow::from_postgres_row_ref`]: trait.FromPostgresRow.html#tymethod.from_postgres_row_ref
+    fn from_postgres_row_borrowed(row: &'a PostgresRow) -> Result<Self, Error>;
+
+    /// Converts from a `postgres` `Row` into a mapped type, borrowing from the
+    /// given `Row` and reading every column with the given prefix prepended to
+    /// its name.
+    ///
+    /// # Errors
+    ///
+    /// Refer to [`from_postgres_row_borrowed`].
+    ///
+    /// [`from_postgres_row_borrowed`]: #tymethod.from_postgres_row_borrowed
+    fn from_postgres_row_borrowed_prefixed(row: &'a PostgresRow, prefix: &str)
+        -> Result<Self, Error>;
+}
+
+/// Trait for types borrowed from the raw value of a column by
+/// [`FromPostgresRowBorrowed`].
+///
+/// This is implemented for `&'a str`, `&'a [u8]` and `Option`s of them.
+///
+/// [`FromPostgresRowBorrowed`]: trait.FromPostgresRowBorrowed.html
+#[cfg(feature = "postgres-support")]
+pub trait FromSqlBorrowed<'a>: Sized {
+    /// Converts the raw value of a column of the given type, `None` if the
+    /// value is NULL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is NULL or not a valid value of the type.
+    fn from_sql_borrowed(ty: &PostgresType, raw: Option<&'a [u8]>)
+        -> Result<Self, Box<dyn StdError + Send + Sync>>;
+
+    /// Whether a column of the given type can be converted.
+    fn accepts(ty: &PostgresType) -> bool;
+}
+
+#[cfg(feature = "postgres-support")]
+impl<'a> FromSqlBorrowed<'a> for &'a str {
+    fn from_sql_borrowed(_: &PostgresType, raw: Option<&'a [u8]>)
+        -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        let raw = raw.ok_or(WasNull)?;
+
+        Ok(::std::str::from_utf8(raw)?)
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        match ty.name() {
+            "text" | "varchar" | "bpchar" | "name" | "unknown" | "citext" => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "postgres-support")]
+impl<'a> FromSqlBorrowed<'a> for &'a [u8] {
+    fn from_sql_borrowed(_: &PostgresType, raw: Option<&'a [u8]>)
+        -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        Ok(raw.ok_or(WasNull)?)
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        ty.name() == "bytea"
+    }
+}
+
+#[cfg(feature = "postgres-support")]
+impl<'a, T: FromSqlBorrowed<'a>> FromSqlBorrowed<'a> for Option<T> {
+    fn from_sql_borrowed(ty: &PostgresType, raw: Option<&'a [u8]>)
+        -> Result<Self, Box<dyn StdError + Send + Sync>> {
+        match raw {
+            Some(raw) => T::from_sql_borrowed(ty, Some(raw)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        T::accepts(ty)
+    }
+}
+
+// Expands to `&'a str` once per tuple element.
+macro_rules! prefix_type {
+    ($lifetime:tt, $ty:ident) => { &$lifetime str };
+}
+
+macro_rules! impl_tuple {
+    ($($ty:ident: $prefix:ident),+) => {
+        #[cfg(feature = "postgres-support")]
+        impl<'a, $($ty: FromPostgresRow),+> FromPostgresRowTuple<'a> for ($($ty,)+) {
+            type Prefixes = ($(prefix_type!('a, $ty),)+);
+
+            fn from_postgres_row_tuple(row: &PostgresRow, prefixes: Self::Prefixes)
+                -> Result<Self, Error> {
+                let ($($prefix,)+) = prefixes;
+
+                Ok(($($ty::from_postgres_row_prefixed(row, $prefix)?,)+))
+            }
+        }
+
+        #[cfg(feature = "tokio-postgres-support")]
+        impl<'a, $($ty: FromTokioPostgresRow),+> FromTokioPostgresRowTuple<'a> for ($($ty,)+) {
+            type Prefixes = ($(prefix_type!('a, $ty),)+);
+
+            fn from_tokio_postgres_row_tuple(row: &TokioRow, prefixes: Self::Prefixes)
+                -> Result<Self, Error> {
+                let ($($prefix,)+) = prefixes;
+
+                Ok(($($ty::from_tokio_postgres_row_prefixed(row, $prefix)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A: a, B: b);
+impl_tuple!(A: a, B: b, C: c);
+impl_tuple!(A: a, B: b, C: c, D: d);
+impl_tuple!(A: a, B: b, C: c, D: d, E: e);
+impl_tuple!(A: a, B: b, C: c, D: d, E: e, F: f);
+impl_tuple!(A: a, B: b, C: c, D: d, E: e, F: f, G: g);
+impl_tuple!(A: a, B: b, C: c, D: d, E: e, F: f, G: g, H: h);
+
+/// Prepends a prefix to a column name.
+///
+/// Used by implementations derived with `postgres_mapper_derive` to look up
+/// prefixed columns.
+#[doc(hidden)]
+pub fn prefix_column<'a>(prefix: &str, column: &'a str) -> Cow<'a, str> {
+    if prefix.is_empty() {
+        Cow::Borrowed(column)
+    } else {
+        Cow::Owned(format!("{}{}", prefix, column))
+    }
+}
+
+/// Used by implementations derived with `postgres_mapper_derive` to list the
+/// columns of every variant of a tagged enum once.
+#[doc(hidden)]
+pub fn merge_columns(parts: &[Vec<String>]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+
+    for column in parts.iter().flatten() {
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+
+    columns
+}
+
+/// Used by implementations derived with `postgres_mapper_derive` to read the
+/// column of a field, or the tag column of an enum when `field` is `None`,
+/// from a `postgres` row.
+#[cfg(feature = "postgres-support")]
+#[doc(hidden)]
+pub fn get_postgres<I, T>(
+    row: &PostgresRow,
+    column: I,
+    struct_name: &'static str,
+    field: Option<&'static str>,
+) -> Result<T, Error>
+    where I: RowIndex + Display,
+          T: PostgresFromSql,
+{
+    let index = column.__idx(row.columns()).ok_or_else(|| Error::ColumnNotFound {
+        column: column.to_string(),
+        struct_name,
+        field,
+    })?;
+    let ty = row.columns()[index].type_();
+
+    if !T::accepts(ty) {
+        return Err(wrong_type::<T, _>(ty, column, struct_name, field));
+    }
+
+    row.get_opt(index)
+        .expect("column index in bounds")
+        .map_err(|error| decode_error(column, struct_name, field, Box::new(error)))
+}
+
+/// Used by implementations derived with `postgres_mapper_derive` to read the
+/// column of a field, or the tag column of an enum when `field` is `None`,
+/// from a `tokio-postgres` row.
+#[cfg(feature = "tokio-postgres-support")]
+#[doc(hidden)]
+pub fn get_tokio_postgres<I, T>(
+    row: &TokioRow,
+    column: I,
+    struct_name: &'static str,
+    field: Option<&'static str>,
+) -> Result<T, Error>
+    where I: TokioRowIndex + Display,
+          T: TokioFromSql,
+{
+    let not_found = || Error::ColumnNotFound {
+        column: column.to_string(),
+        struct_name,
+        field,
+    };
+    let index = column.__idx(row.columns()).ok_or_else(not_found)?;
+    let ty = row.columns()[index].type_();
+
+    if !T::accepts(ty) {
+        return Err(wrong_type::<T, _>(ty, &column, struct_name, field));
+    }
+
+    match row.try_get(index) {
+        Ok(value) => value.ok_or_else(not_found),
+        Err(error) => Err(decode_error(column, struct_name, field, error)),
+    }
+}
+
+/// The error for a column of type `ty` that can't be read as a `T`.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn wrong_type<T, I: Display>(
+    ty: &PostgresType,
+    column: I,
+    struct_name: &'static str,
+    field: Option<&'static str>,
+) -> Error {
+    Error::WrongType {
+        column: column.to_string(),
+        struct_name,
+        field,
+        rust_type: type_name::<T>(),
+        sql_type: ty.clone(),
+    }
+}
+
+/// The error for a column whose value failed to be read as a field's type.
+#[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+fn decode_error<I: Display>(
+    column: I,
+    struct_name: &'static str,
+    field: Option<&'static str>,
+    error: Box<dyn StdError + Send + Sync>,
+) -> Error {
+    Error::Decode {
+        column: column.to_string(),
+        struct_name,
+        field,
+        error,
+    }
+}
+
+/// Used by implementations derived with `postgres_mapper_derive` to read a
+/// borrowed field of a [`FromPostgresRowBorrowed`] implementation.
+///
+/// [`FromPostgresRowBorrowed`]: trait.FromPostgresRowBorrowed.html
+#[cfg(feature = "postgres-support")]
+#[doc(hidden)]
+pub fn get_borrowed<'a, I, T>(
+    row: &'a PostgresRow,
+    column: I,
+    struct_name: &'static str,
+    field: &'static str,
+) -> Result<T, Error>
+    where I: RowIndex + Display,
+          T: FromSqlBorrowed<'a>,
+{
+    let index = column.__idx(row.columns()).ok_or_else(|| Error::ColumnNotFound {
+        column: column.to_string(),
+        struct_name,
+        field: Some(field),
+    })?;
+    let ty = row.columns()[index].type_();
+
+    if !T::accepts(ty) {
+        return Err(wrong_type::<T, _>(ty, column, struct_name, Some(field)));
+    }
+
+    T::from_sql_borrowed(ty, row.get_bytes(index))
+        .map_err(|error| decode_error(column, struct_name, Some(field), error))
+}
+
+/// Used by implementations derived with `postgres_mapper_derive` to collect
+/// the error of a field mapped by the `*_all` mapper methods, flattening the
+/// errors of nested mapped types.
+#[doc(hidden)]
+pub fn collect_error<T>(result: Result<T, Error>, errors: &mut Vec<Error>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(Error::Multiple(nested)) => {
+            errors.extend(nested);
+
+            None
+        },
+        Err(error) => {
+            errors.push(error);
+
+            None
+        },
+    }
+}
+
+/// The value a `json` or `jsonb` column is read as before being deserialized.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub type JsonValue = serde_json::Value;
+
+/// Deserializes the value of a `json` or `jsonb` column.
+///
+/// A `NULL` column is deserialized from JSON's `null`, so that `Option` fields
+/// map it to `None`.
+///
+/// Used by implementations derived with `postgres_mapper_derive` for fields
+/// marked with `#[pg_mapper(json)]`.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub fn from_json<T: DeserializeOwned>(value: Option<JsonValue>, column: &str) -> Result<T, Error> {
+    let value = value.unwrap_or(JsonValue::Null);
+
+    serde_json::from_value(value).map_err(|error| Error::Json {
+        column: column.to_owned(),
+        error,
+    })
 }
 
 /// General error type returned throughout the library.
+///
+/// Errors wrapping an underlying error, such as one from `postgres` or a
+/// field's conversion function, describe what was being mapped when
+/// displayed and return the underlying error from [`source`]. `Error` is
+/// `Send`, `Sync` and `'static`, so it can be boxed into other error types.
+///
+/// The mapper methods return the error of the first field that could not be
+/// mapped, such as [`ColumnNotFound`] for a missing column, [`WrongType`] or
+/// [`Decode`] for a column that can't be read as its field's type, or the error
+/// of a field's `with`, `from_sql`, `try_from` or `json` conversion. The
+/// `*_all` methods return every such error within [`Multiple`].
+///
+/// [`ColumnNotFound`]: #variant.ColumnNotFound
+/// [`WrongType`]: #variant.WrongType
+/// [`Decode`]: #variant.Decode
+/// [`Multiple`]: #variant.Multiple
+/// [`source`]: https://doc.rust-lang.org/std/error/trait.Error.html#method.source
 #[derive(Debug)]
 pub enum Error {
     /// A column in a row was not found.
-    ColumnNotFound,
-    /// An error from the `tokio-postgres` crate while converting a type.
+    ColumnNotFound {
+        /// The name of the column, including any prefix, or its index.
+        column: String,
+        /// The name of the struct being mapped, or `Enum::Variant` for the
+        /// variants of a tagged enum.
+        struct_name: &'static str,
+        /// The name of the field read from the column, or `None` for the tag
+        /// column of an enum.
+        field: Option<&'static str>,
+    },
+    /// A column's Postgres type can not be read as the Rust type of its field.
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+    WrongType {
+        /// The name of the column, including any prefix, or its index.
+        column: String,
+        /// The name of the struct being mapped, or `Enum::Variant` for the
+        /// variants of a tagged enum.
+        struct_name: &'static str,
+        /// The name of the field read from the column, or `None` for the tag
+        /// column of an enum.
+        field: Option<&'static str>,
+        /// The name of the Rust type the column was read as.
+        rust_type: &'static str,
+        /// The Postgres type of the column.
+        sql_type: PostgresType,
+    },
+    /// A column's value could not be read as the Rust type of its field, such
+    /// as a `NULL` read into a field that isn't an `Option`.
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+    Decode {
+        /// The name of the column, including any prefix, or its index.
+        column: String,
+        /// The name of the struct being mapped, or `Enum::Variant` for the
+        /// variants of a tagged enum.
+        struct_name: &'static str,
+        /// The name of the field read from the column, or `None` for the tag
+        /// column of an enum.
+        field: Option<&'static str>,
+        /// The error returned by the `postgres` or `tokio-postgres` crate.
+        error: Box<dyn StdError + Send + Sync>,
+    },
+    /// An error from the `tokio-postgres` crate, converted with `From`.
+    ///
+    /// The derived mappers return [`Error::Decode`] instead, naming the column
+    /// that could not be read.
+    ///
+    /// [`Error::Decode`]: #variant.Decode
     #[cfg(feature = "tokio-postgres-support")]
-    Conversion(Box<StdError + Send + Sync>),
-    /// An error from the `postgres` crate while converting a type.
+    Conversion(Box<dyn StdError + Send + Sync>),
+    /// An error from the `postgres` crate, converted with `From`.
+    ///
+    /// The derived mappers return [`Error::Decode`] instead, naming the column
+    /// that could not be read.
+    ///
+    /// [`Error::Decode`]: #variant.Decode
     #[cfg(feature = "postgres-support")]
     Postgres(PostgresError),
+    /// An error from a field's `with` or `from_sql` conversion function.
+    Custom {
+        /// The name of the field being converted.
+        field: &'static str,
+        /// The error returned by the conversion function.
+        error: Box<dyn StdError + Send + Sync>,
+    },
+    /// An error from a field's `TryFrom` conversion declared with `try_from`.
+    TryFrom {
+        /// The name of the field being converted.
+        field: &'static str,
+        /// The error returned by `TryFrom::try_from`.
+        error: Box<dyn StdError + Send + Sync>,
+    },
+    /// An error from `serde_json` while deserializing a `json` field.
+    #[cfg(feature = "serde")]
+    Json {
+        /// The name of the column being deserialized.
+        column: String,
+        /// The error returned by `serde_json`.
+        error: JsonError,
+    },
+    /// The tag column of an enum held a tag matching none of its variants.
+    UnknownTag {
+        /// The name of the tag column.
+        column: String,
+        /// The tag read from the column.
+        tag: String,
+    },
+    /// A `*_prefixed` mapper method was called with a prefix for a type
+    /// reading some of its fields by position, which the prefix can't apply
+    /// to.
+    PositionalPrefix {
+        /// The name of the mapped type.
+        struct_name: &'static str,
+        /// The prefix passed to the mapper method.
+        prefix: String,
+    },
+    /// The errors of every field that could not be mapped, returned by the
+    /// `*_all` mapper methods.
+    Multiple(Vec<Error>),
 }
 
 #[cfg(feature = "tokio-postgres-support")]
-impl From<Box<StdError + Send + Sync>> for Error {
-    fn from(err: Box<StdError + Send + Sync>) -> Self {
+impl From<Box<dyn StdError + Send + Sync>> for Error {
+    fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
         Error::Conversion(err)
     }
 }
@@ -352,18 +1250,184 @@ impl From<PostgresError> for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        f.write_str(self.description())
+        match *self {
+            Error::ColumnNotFound { ref column, struct_name, field: Some(field) } => write!(
+                f,
+                "Column `{}` in row not found, mapping field `{}` of `{}`",
+                column,
+                field,
+                struct_name,
+            ),
+            Error::ColumnNotFound { ref column, struct_name, field: None } => write!(
+                f,
+                "Column `{}` in row not found, mapping the tag of `{}`",
+                column,
+                struct_name,
+            ),
+            #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+            Error::WrongType { ref column, struct_name, field, rust_type, ref sql_type } => {
+                write!(
+                    f,
+                    "Column `{}` of type {} can not be read as `{}`, mapping ",
+                    column,
+                    sql_type,
+                    rust_type,
+                )?;
+
+                match field {
+                    Some(field) => write!(f, "field `{}` of `{}`", field, struct_name),
+                    None => write!(f, "the tag of `{}`", struct_name),
+                }
+            },
+            #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+            Error::Decode { ref column, struct_name, field, .. } => {
+                write!(f, "Error reading column `{}`, mapping ", column)?;
+
+                match field {
+                    Some(field) => write!(f, "field `{}` of `{}`", field, struct_name),
+                    None => write!(f, "the tag of `{}`", struct_name),
+                }
+            },
+            #[cfg(feature = "tokio-postgres-support")]
+            Error::Conversion(_) => f.write_str("Error from the `tokio-postgres` crate"),
+            #[cfg(feature = "postgres-support")]
+            Error::Postgres(_) => f.write_str("Error from the `postgres` crate"),
+            Error::Custom { field, .. } => write!(f, "Error converting field `{}`", field),
+            Error::TryFrom { field, .. } => {
+                write!(f, "Error converting field `{}` with `TryFrom`", field)
+            },
+            #[cfg(feature = "serde")]
+            Error::Json { ref column, .. } => {
+                write!(f, "Error deserializing JSON from column `{}`", column)
+            },
+            Error::UnknownTag { ref column, ref tag } => {
+                write!(f, "Unknown tag `{}` in tag column `{}`", tag, column)
+            },
+            Error::PositionalPrefix { struct_name, ref prefix } => write!(
+                f,
+                "Can not read the positional fields of `{}` with the column prefix `{}`",
+                struct_name,
+                prefix,
+            ),
+            Error::Multiple(ref errors) => {
+                match errors.len() {
+                    1 => f.write_str("1 error mapping row")?,
+                    len => write!(f, "{} errors mapping row", len)?,
+                }
+
+                for (index, error) in errors.iter().enumerate() {
+                    write!(f, "{} {}", if index == 0 { ":" } else { ";" }, error)?;
+
+                    let mut source = error.source();
+
+                    while let Some(error) = source {
+                        write!(f, ": {}", error)?;
+                        source = error.source();
+                    }
+                }
+
+                Ok(())
+            },
+        }
     }
 }
 
 impl StdError for Error {
-    fn description(&self) -> &str {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match *self {
-            Error::ColumnNotFound => "Column in row not found",
             #[cfg(feature = "tokio-postgres-support")]
-            Error::Conversion(ref inner) => inner.description(),
+            Error::Conversion(ref inner) => Some(&**inner),
             #[cfg(feature = "postgres-support")]
-            Error::Postgres(ref inner) => inner.description(),
+            Error::Postgres(ref inner) => Some(inner),
+            #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+            Error::Decode { ref error, .. } => Some(&**error),
+            Error::Custom { ref error, .. } | Error::TryFrom { ref error, .. } => Some(&**error),
+            #[cfg(feature = "serde")]
+            Error::Json { ref error, .. } => Some(error),
+            _ => None,
         }
     }
 }
+
+/// `Error` is sent across threads and boxed into `'static` error types.
+#[allow(dead_code)]
+fn assert_error_send_sync() {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    assert_send_sync::<Error>();
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+    use super::decode_error;
+    #[cfg(feature = "serde")]
+    use super::{from_json, JsonValue};
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support", feature = "serde"))]
+    use super::Error;
+
+    #[test]
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+    fn decode_display() {
+        use std::error::Error as StdError;
+
+        let error = decode_error("u_email", "User", Some("email"), "unexpected NULL".into());
+        assert_eq!(
+            error.to_string(),
+            "Error reading column `u_email`, mapping field `email` of `User`",
+        );
+        assert_eq!(error.source().unwrap().to_string(), "unexpected NULL");
+
+        let error = decode_error(0, "Event", None, "invalid UTF-8".into());
+        assert_eq!(error.to_string(), "Error reading column `0`, mapping the tag of `Event`");
+    }
+
+    #[test]
+    #[cfg(any(feature = "postgres-support", feature = "tokio-postgres-support"))]
+    fn multiple_display() {
+        let error = Error::Multiple(vec![
+            decode_error("email", "User", Some("email"), "unexpected NULL".into()),
+            Error::ColumnNotFound {
+                column: "id".to_owned(),
+                struct_name: "User",
+                field: Some("id"),
+            },
+        ]);
+
+        assert_eq!(
+            error.to_string(),
+            "2 errors mapping row: Error reading column `email`, mapping field `email` of \
+             `User`: unexpected NULL; Column `id` in row not found, mapping field `id` of `User`",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn from_json_null() {
+        let value: Option<Vec<i32>> = from_json(None, "settings").unwrap();
+        assert_eq!(value, None);
+
+        let value: Option<Vec<i32>> = from_json(Some(JsonValue::Null), "settings").unwrap();
+        assert_eq!(value, None);
+
+        match from_json::<Vec<i32>>(None, "settings") {
+            Err(Error::Json { ref column, .. }) => assert_eq!(column, "settings"),
+            other => panic!("expected Error::Json, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn from_json_value() {
+        let value: Option<Vec<i32>> = from_json(Some(json_array()), "settings").unwrap();
+        assert_eq!(value, Some(vec![1, 2]));
+
+        let value: Vec<i32> = from_json(Some(json_array()), "settings").unwrap();
+        assert_eq!(value, vec![1, 2]);
+    }
+
+    #[cfg(feature = "serde")]
+    fn json_array() -> JsonValue {
+        JsonValue::Array(vec![JsonValue::from(1), JsonValue::from(2)])
+    }
+}